@@ -0,0 +1,110 @@
+//! Placeholder substitution for sharing a work history as a sample.
+//! 
+//! Unlike blanking fields, anonymizing keeps the output's structure: each
+//! distinct company gets a consistent placeholder ("Company A", "Company B",
+//! ...), and dates and responsibilities are left as they are.
+
+use crate::history::WorkHistory;
+
+/// Placeholder written in place of every location.
+pub const PLACEHOLDER_LOCATION: &str = "City, ST";
+
+/// Placeholder written in place of every supervisor (reference) name.
+pub const PLACEHOLDER_SUPERVISOR: &str = "Supervisor";
+
+/// Replaces identifying fields with placeholders.
+/// 
+/// Companies are labeled in order of first appearance, matching names
+/// case-insensitively, so the same company always gets the same label.
+/// Supervisor names become [`PLACEHOLDER_SUPERVISOR`] and locations become
+/// [`PLACEHOLDER_LOCATION`]; display names, company websites, reference
+/// contacts, and ZIP codes are dropped.
+/// 
+/// # Arguments
+/// * `work_histories` - The entries to anonymize in place, in output order
+pub fn anonymize(work_histories: &mut [WorkHistory]) {
+    let mut companies: Vec<String> = Vec::new();
+
+    for history in work_histories {
+        let key = history.company.trim().to_lowercase();
+        let index = match companies.iter().position(|company| *company == key) {
+            Some(index) => index,
+            None => {
+                companies.push(key);
+                companies.len() - 1
+            }
+        };
+
+        history.company = format!("Company {}", company_label(index));
+        history.display_name = None;
+        history.company_url = None;
+        if history.reference_name.is_some() {
+            history.reference_name = Some(PLACEHOLDER_SUPERVISOR.to_string());
+        }
+        history.reference_contact = None;
+        history.postal_code = None;
+        if !history.location.is_empty() {
+            history.location = PLACEHOLDER_LOCATION.to_string();
+        }
+    }
+}
+
+/// Letters labeling the n-th company: "A" to "Z", then "AA", "AB", ...
+/// 
+/// # Arguments
+/// * `index` - Zero-based position of the company
+/// 
+/// # Returns
+/// * `String` - The label
+fn company_label(mut index: usize) -> String {
+    let mut label = Vec::new();
+    loop {
+        label.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    label.reverse();
+    String::from_utf8(label).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::entry;
+
+    #[test]
+    fn companies_get_stable_placeholders() {
+        let mut histories = vec![
+            entry("Initech", "01/01/2022", "Present"),
+            entry("Acme", "01/01/2020", "01/01/2022"),
+            entry("initech ", "01/01/2018", "01/01/2020"),
+        ];
+        histories[1].display_name = Some("Acme Labs".to_string());
+        histories[1].company_url = Some("https://acme.com".to_string());
+        histories[1].reference_name = Some("Jane Doe".to_string());
+        histories[1].reference_contact = Some("jane@acme.com".to_string());
+
+        anonymize(&mut histories);
+        let companies: Vec<&str> = histories.iter().map(|h| h.company.as_str()).collect();
+        assert_eq!(companies, ["Company A", "Company B", "Company A"]);
+        assert_eq!(histories[1].display_company(), "Company B");
+        assert_eq!(histories[1].company_url, None);
+        assert_eq!(histories[1].reference_name.as_deref(), Some(PLACEHOLDER_SUPERVISOR));
+        assert_eq!(histories[0].reference_name, None);
+        assert_eq!(histories[1].reference_contact, None);
+        assert!(histories.iter().all(|h| h.location == PLACEHOLDER_LOCATION));
+        assert_eq!(histories[0].responsibilities, "Build things");
+        assert_eq!(histories[0].end_date, None);
+    }
+
+    #[test]
+    fn company_labels_continue_past_z() {
+        assert_eq!(company_label(0), "A");
+        assert_eq!(company_label(25), "Z");
+        assert_eq!(company_label(26), "AA");
+        assert_eq!(company_label(27), "AB");
+        assert_eq!(company_label(26 + 26 * 26), "AAA");
+    }
+}
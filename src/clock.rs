@@ -0,0 +1,48 @@
+//! The source of "today" for date-relative features.
+//! 
+//! Ongoing jobs are measured until today (durations, tenure filters, skill
+//! totals, sanity checks). Asking a [`Clock`] instead of the system time
+//! lets those features be tested against a fixed date.
+
+use chrono::{Local, NaiveDate};
+use std::fmt::Debug;
+
+/// Provides the current date.
+pub trait Clock: Debug {
+    /// The current date.
+    fn today(&self) -> NaiveDate;
+}
+
+/// The system clock, in the local time zone.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn today(&self) -> NaiveDate {
+        Local::now().date_naive()
+    }
+}
+
+/// A clock that always returns the same date.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedClock(pub NaiveDate);
+
+impl Clock for FixedClock {
+    fn today(&self) -> NaiveDate {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::Options;
+
+    #[test]
+    fn options_ask_their_clock_for_today() {
+        let date = NaiveDate::from_ymd_opt(2022, 6, 1).unwrap();
+        let options = Options { clock: Some(Box::new(FixedClock(date))), ..Options::default() };
+        assert_eq!(options.today(), date);
+        assert_eq!(Options::default().today(), SystemClock.today());
+    }
+}
@@ -0,0 +1,318 @@
+//! Mapping of input columns to work history fields by header name.
+//! 
+//! The original input layout is positional (Company, Job Title, Start Date,
+//! End Date, Address, Supervisor Name, Description, Reason). Columns are now
+//! located by their header names first, so files with extra or reordered
+//! columns still parse, falling back to the original positions for the core
+//! fields when a header isn't recognized. Optional fields such as Department,
+//! Salary, Skills, Keywords, Website, and Display Name are only read when
+//! their header is present.
+
+use csv::StringRecord;
+
+use crate::error::InputError;
+
+/// A field that can be read from the input CSV.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Company,
+    Position,
+    StartDate,
+    EndDate,
+    Address,
+    Supervisor,
+    Description,
+    Reason,
+    Department,
+    Salary,
+    Skills,
+    DisplayName,
+    EmploymentType,
+    ReferenceName,
+    ReferenceContact,
+    Keywords,
+    Website,
+}
+
+impl Field {
+    /// Every known field, in the original column order.
+    pub const ALL: [Field; 17] = [
+        Field::Company,
+        Field::Position,
+        Field::StartDate,
+        Field::EndDate,
+        Field::Address,
+        Field::Supervisor,
+        Field::Description,
+        Field::Reason,
+        Field::Department,
+        Field::Salary,
+        Field::Skills,
+        Field::DisplayName,
+        Field::EmploymentType,
+        Field::ReferenceName,
+        Field::ReferenceContact,
+        Field::Keywords,
+        Field::Website,
+    ];
+
+    /// Name of the field as written in the original header row.
+    pub fn name(self) -> &'static str {
+        match self {
+            Field::Company => "Company",
+            Field::Position => "Job Title",
+            Field::StartDate => "Start Date",
+            Field::EndDate => "End Date",
+            Field::Address => "Address",
+            Field::Supervisor => "Supervisor Name",
+            Field::Description => "Description",
+            Field::Reason => "Reason",
+            Field::Department => "Department",
+            Field::Salary => "Salary",
+            Field::Skills => "Skills",
+            Field::DisplayName => "Display Name",
+            Field::EmploymentType => "Type",
+            Field::ReferenceName => "Reference Name",
+            Field::ReferenceContact => "Reference Contact",
+            Field::Keywords => "Keywords",
+            Field::Website => "Website",
+        }
+    }
+
+    /// Looks up a field by any of its recognized header names, e.g. "start"
+    /// or "Start Date", ignoring case and punctuation.
+    pub fn from_name(name: &str) -> Option<Field> {
+        let name = normalize_header(name);
+        Field::ALL.into_iter().find(|field| field.header_names().contains(&name.as_str()))
+    }
+
+    /// Example value of the field, for the `--emit-template` dummy row.
+    pub fn example(self) -> &'static str {
+        match self {
+            Field::Company => "Acme Corp",
+            Field::Position => "Software Engineer",
+            Field::StartDate => "01/15/2020",
+            Field::EndDate => "Present",
+            Field::Address => "123 Main St, Springfield, IL 62701",
+            Field::Supervisor => "Jane Doe",
+            Field::Description => "Built the billing system",
+            Field::Reason => "",
+            Field::Department => "Engineering",
+            Field::Salary => "85000",
+            Field::Skills => "Rust, SQL",
+            Field::DisplayName => "Acme",
+            Field::EmploymentType => "Full-time",
+            Field::ReferenceName => "Jane Doe",
+            Field::ReferenceContact => "jane.doe@acme.com",
+            Field::Keywords => "billing, payments",
+            Field::Website => "https://acme.example.com",
+        }
+    }
+
+    /// Normalized header names recognized for the field.
+    fn header_names(self) -> &'static [&'static str] {
+        match self {
+            Field::Company => &["company", "company name", "employer"],
+            Field::Position => &["job title", "title", "position"],
+            Field::StartDate => &["start date", "start"],
+            Field::EndDate => &["end date", "end"],
+            Field::Address => &["address", "location"],
+            Field::Supervisor => &["supervisor name", "supervisor"],
+            Field::Description => &["description", "description of responsibilities", "responsibilities"],
+            Field::Reason => &["reason", "reason for leaving"],
+            Field::Department => &["department", "dept"],
+            Field::Salary => &["salary", "pay", "compensation"],
+            Field::Skills => &["skills", "skill tags"],
+            Field::DisplayName => &["display name", "preferred name", "company display name"],
+            Field::EmploymentType => &["type", "employment type", "job type"],
+            Field::ReferenceName => &["reference name", "reference"],
+            Field::ReferenceContact => &["reference contact", "reference phone", "reference email"],
+            Field::Keywords => &["keywords", "keyword", "tags", "ats keywords"],
+            Field::Website => &["website", "company url", "url", "company website"],
+        }
+    }
+
+    /// Column the field occupies in the original positional layout, if any.
+    fn default_index(self) -> Option<usize> {
+        match self {
+            Field::Company => Some(0),
+            Field::Position => Some(1),
+            Field::StartDate => Some(2),
+            Field::EndDate => Some(3),
+            Field::Address => Some(4),
+            Field::Supervisor => Some(5),
+            Field::Description => Some(6),
+            Field::Reason => Some(7),
+            Field::Department | Field::Salary | Field::Skills | Field::DisplayName | Field::EmploymentType | Field::ReferenceName | Field::ReferenceContact | Field::Keywords | Field::Website => None,
+        }
+    }
+}
+
+/// Normalizes a header for matching: lowercase, with runs of punctuation and
+/// whitespace collapsed to single spaces.
+/// 
+/// # Arguments
+/// * `header` - The raw header cell
+/// 
+/// # Returns
+/// * `String` - The normalized header
+fn normalize_header(header: &str) -> String {
+    header
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Column index of each field in a particular input file.
+#[derive(Debug, Clone, Default)]
+pub struct ColumnMap {
+    indices: [Option<usize>; Field::ALL.len()],
+}
+
+impl ColumnMap {
+    /// Builds the column map from a header row.
+    /// 
+    /// Fields are matched by header name; core fields whose header isn't
+    /// recognized fall back to their original position when that column
+    /// exists and isn't already claimed by another field.
+    /// 
+    /// # Arguments
+    /// * `headers` - The header row of the input
+    /// 
+    /// # Returns
+    /// * `Result<ColumnMap, InputError>` - The resolved column for each field
+    /// 
+    /// # Errors
+    /// Returns [`InputError::DuplicateColumn`] if two headers name the same field
+    pub fn from_headers(headers: &StringRecord) -> Result<Self, InputError> {
+        let mut map = ColumnMap::default();
+
+        for (index, header) in headers.iter().enumerate() {
+            if let Some(field) = Field::from_name(header) {
+                if let Some(first) = map.get(field) {
+                    return Err(InputError::DuplicateColumn {
+                        field: field.name().to_string(),
+                        first,
+                        second: index,
+                    });
+                }
+                map.indices[field as usize] = Some(index);
+            }
+        }
+
+        for field in Field::ALL {
+            if map.get(field).is_some() {
+                continue;
+            }
+            if let Some(index) = field.default_index() {
+                let claimed = map.indices.contains(&Some(index));
+                if index < headers.len() && !claimed {
+                    map.indices[field as usize] = Some(index);
+                }
+            }
+        }
+
+        Ok(map)
+    }
+
+    /// Checks that the input has every required field, for `--require-columns`.
+    /// 
+    /// # Arguments
+    /// * `required` - The fields that must be present
+    /// 
+    /// # Errors
+    /// Returns [`InputError::MissingColumns`] naming every missing field
+    pub fn require(&self, required: &[Field]) -> Result<(), InputError> {
+        let missing: Vec<String> = required
+            .iter()
+            .filter(|field| self.get(**field).is_none())
+            .map(|field| field.name().to_string())
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(InputError::MissingColumns { columns: missing })
+        }
+    }
+
+    /// Column index of a field, if the input has it.
+    pub fn get(&self, field: Field) -> Option<usize> {
+        self.indices[field as usize]
+    }
+
+    /// Value of a field in a record, or `None` if the input has no such
+    /// column or the row is too short.
+    pub fn value<'a>(&self, record: &'a StringRecord, field: Field) -> Option<&'a str> {
+        self.get(field).and_then(|index| record.get(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn original_headers_map_positionally() {
+        let headers = StringRecord::from(vec![
+            "Company", "Job Title", "Start Date", "End Date", "Address",
+            "Supervisor Name", "Description of Responsibilities", "Reason for Leaving",
+        ]);
+        let map = ColumnMap::from_headers(&headers).unwrap();
+
+        for field in &Field::ALL[..8] {
+            assert_eq!(map.get(*field), field.default_index());
+        }
+        assert_eq!(map.get(Field::Department), None);
+    }
+
+    #[test]
+    fn headers_are_matched_by_name_in_any_order() {
+        let headers = StringRecord::from(vec!["Dept.", "Job Title", "COMPANY", "start_date", "End Date"]);
+        let map = ColumnMap::from_headers(&headers).unwrap();
+
+        assert_eq!(map.get(Field::Department), Some(0));
+        assert_eq!(map.get(Field::Position), Some(1));
+        assert_eq!(map.get(Field::Company), Some(2));
+        assert_eq!(map.get(Field::StartDate), Some(3));
+        assert_eq!(map.get(Field::EndDate), Some(4));
+        assert_eq!(map.get(Field::Address), None);
+    }
+
+    #[test]
+    fn unrecognized_headers_fall_back_to_original_positions() {
+        let headers = StringRecord::from(vec!["Employer Name", "Role", "From", "To", "Where", "Boss", "Duties", "Why"]);
+        let map = ColumnMap::from_headers(&headers).unwrap();
+
+        assert_eq!(map.get(Field::Company), Some(0));
+        assert_eq!(map.get(Field::Description), Some(6));
+    }
+
+    #[test]
+    fn duplicate_headers_are_rejected_with_both_columns() {
+        let headers = StringRecord::from(vec!["Company", "Job Title", "Start Date", "Company"]);
+        assert_eq!(
+            ColumnMap::from_headers(&headers).unwrap_err(),
+            InputError::DuplicateColumn { field: "Company".to_string(), first: 0, second: 3 }
+        );
+
+        // Different aliases of one field are duplicates too
+        let headers = StringRecord::from(vec!["Employer", "Title", "Company Name"]);
+        let err = ColumnMap::from_headers(&headers).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The header has two \"Company\" columns, 0 and 2 (zero-based); remove or rename one"
+        );
+    }
+
+    #[test]
+    fn field_names_map_back_to_their_fields() {
+        let headers = StringRecord::from(Field::ALL.map(Field::name).to_vec());
+        let map = ColumnMap::from_headers(&headers).unwrap();
+
+        for (index, field) in Field::ALL.into_iter().enumerate() {
+            assert_eq!(map.get(field), Some(index), "{}", field.name());
+        }
+    }
+}
@@ -0,0 +1,515 @@
+//! The `--entry-filter` expression language, for keeping only the entries
+//! that match a condition such as `company contains "Acme" and duration > 12mo`.
+//! 
+//! # Grammar
+//! ```text
+//! expr       := and_expr ("or" and_expr)*
+//! and_expr   := unary ("and" unary)*
+//! unary      := "not" unary | "(" expr ")" | comparison
+//! comparison := field operator value
+//! field      := "company" | "position" | "location" | "duration" | "start" | "end"
+//! operator   := "contains" | "=" | "==" | "!=" | "<" | "<=" | ">" | ">="
+//! value      := "\"" text "\"" | word
+//! ```
+//! 
+//! Keywords and field names ignore case, and `and` binds tighter than `or`.
+//! A word is any run of characters other than whitespace, parentheses,
+//! quotes, and the operator characters `<>=!`; quote values with spaces, and
+//! escape a quote inside one as `\"`.
+//! 
+//! - `company`, `position`, and `location` take `contains`, `=`, and `!=`,
+//!   comparing text without regard to case.
+//! - `duration` is the tenure in whole months, with ongoing jobs measured to
+//!   today, and takes `=`, `!=`, `<`, `<=`, `>`, and `>=`. Values are a number
+//!   of months (`18`, `18mo`) or years (`2y`).
+//! - `start` and `end` take the same operators as `duration`, with a date in
+//!   any input format (e.g. `01/15/2020` or `"Jan 2020"`), as YYYY-MM-DD, or
+//!   `present` for today. An ongoing job ends today.
+//! 
+//! Undated entries have no duration, start, or end, so comparisons of those
+//! are false for them.
+
+use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
+
+use crate::history::WorkHistory;
+use crate::input::parse_date;
+
+/// A parsed `--entry-filter` expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntryFilter {
+    expr: Expr,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(FilterField, Operator, Value),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FilterField {
+    Company,
+    Position,
+    Location,
+    Duration,
+    Start,
+    End,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    Contains,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    /// Lowercased text
+    Text(String),
+    Months(i32),
+    /// A date, or `None` for today
+    Date(Option<NaiveDate>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Operator(&'static str),
+    Open,
+    Close,
+}
+
+impl FilterField {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "company" => Some(FilterField::Company),
+            "position" | "title" => Some(FilterField::Position),
+            "location" => Some(FilterField::Location),
+            "duration" => Some(FilterField::Duration),
+            "start" => Some(FilterField::Start),
+            "end" => Some(FilterField::End),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            FilterField::Company => "company",
+            FilterField::Position => "position",
+            FilterField::Location => "location",
+            FilterField::Duration => "duration",
+            FilterField::Start => "start",
+            FilterField::End => "end",
+        }
+    }
+
+    fn is_text(self) -> bool {
+        matches!(self, FilterField::Company | FilterField::Position | FilterField::Location)
+    }
+}
+
+impl EntryFilter {
+    /// Parses an `--entry-filter` expression.
+    /// 
+    /// # Arguments
+    /// * `input` - The expression, e.g. `company contains "Acme" and duration > 12mo`
+    /// 
+    /// # Returns
+    /// * `Result<EntryFilter>` - The parsed filter
+    /// 
+    /// # Errors
+    /// Returns an error naming the column where the expression stops making
+    /// sense and what was expected there, e.g. "Invalid --entry-filter at
+    /// column 9: expected an operator, found \"Acme\""
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut parser = Parser { input, tokens: tokenize(input)?, position: 0 };
+        let expr = parser.expr()?;
+        if parser.position < parser.tokens.len() {
+            return Err(parser.error("\"and\", \"or\", or the end of the filter"));
+        }
+        Ok(EntryFilter { expr })
+    }
+
+    /// Whether an entry matches the filter.
+    /// 
+    /// # Arguments
+    /// * `history` - The entry to test
+    /// * `today` - The date ongoing jobs are measured to, and `present` means
+    /// 
+    /// # Returns
+    /// * `bool` - True if the entry should be kept
+    pub fn matches(&self, history: &WorkHistory, today: NaiveDate) -> bool {
+        evaluate(&self.expr, history, today)
+    }
+}
+
+fn evaluate(expr: &Expr, history: &WorkHistory, today: NaiveDate) -> bool {
+    match expr {
+        Expr::And(left, right) => evaluate(left, history, today) && evaluate(right, history, today),
+        Expr::Or(left, right) => evaluate(left, history, today) || evaluate(right, history, today),
+        Expr::Not(inner) => !evaluate(inner, history, today),
+        Expr::Compare(field, operator, value) => {
+            let text = match field {
+                FilterField::Company => Some(&history.company),
+                FilterField::Position => Some(&history.position),
+                FilterField::Location => Some(&history.location),
+                _ => None,
+            };
+            match (text, value) {
+                (Some(text), Value::Text(value)) => {
+                    let text = text.to_lowercase();
+                    match operator {
+                        Operator::Contains => text.contains(value.as_str()),
+                        Operator::Ne => text != *value,
+                        _ => text == *value,
+                    }
+                }
+                (_, Value::Months(months)) => {
+                    history.tenure_months(today).is_some_and(|tenure| compare(*operator, tenure, *months))
+                }
+                (_, Value::Date(date)) => {
+                    let entry_date = match field {
+                        FilterField::Start => history.start_date,
+                        _ => history.start_date.map(|_| history.end_date.unwrap_or(today)),
+                    };
+                    entry_date.is_some_and(|entry_date| compare(*operator, entry_date, date.unwrap_or(today)))
+                }
+                _ => false,
+            }
+        }
+    }
+}
+
+fn compare<T: PartialOrd>(operator: Operator, left: T, right: T) -> bool {
+    match operator {
+        Operator::Eq | Operator::Contains => left == right,
+        Operator::Ne => left != right,
+        Operator::Lt => left < right,
+        Operator::Le => left <= right,
+        Operator::Gt => left > right,
+        Operator::Ge => left >= right,
+    }
+}
+
+/// Splits an expression into tokens, each with the byte offset it starts at.
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        chars.next();
+        let token = match c {
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '"' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) if chars.peek().is_some() => text.push(chars.next().unwrap().1),
+                        Some((_, c)) => text.push(c),
+                        None => {
+                            return Err(anyhow!(
+                                "Invalid --entry-filter at column {}: unterminated quoted value",
+                                column(input, start)
+                            ))
+                        }
+                    }
+                }
+                Token::Quoted(text)
+            }
+            '<' | '>' | '=' | '!' => {
+                let equals = chars.next_if(|&(_, next)| next == '=').is_some();
+                Token::Operator(match (c, equals) {
+                    ('<', false) => "<",
+                    ('<', true) => "<=",
+                    ('>', false) => ">",
+                    ('>', true) => ">=",
+                    ('=', _) => "=",
+                    ('!', true) => "!=",
+                    _ => {
+                        return Err(anyhow!(
+                            "Invalid --entry-filter at column {}: expected \"!=\", found \"!\"",
+                            column(input, start)
+                        ))
+                    }
+                })
+            }
+            _ => {
+                let mut word = c.to_string();
+                while let Some((_, c)) = chars.next_if(|&(_, c)| !c.is_whitespace() && !"()\"<>=!".contains(c)) {
+                    word.push(c);
+                }
+                Token::Word(word)
+            }
+        };
+        tokens.push((start, token));
+    }
+
+    Ok(tokens)
+}
+
+/// One-based character column of a byte offset, for error messages.
+fn column(input: &str, offset: usize) -> usize {
+    input[..offset].chars().count() + 1
+}
+
+/// Parses a duration value: whole months, or years with a "y" suffix.
+fn parse_months(value: &str) -> Option<i32> {
+    let digits = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let number: i32 = value[..digits].parse().ok()?;
+    match value[digits..].to_lowercase().as_str() {
+        "" | "m" | "mo" | "month" | "months" => Some(number),
+        "y" | "yr" | "yrs" | "year" | "years" => number.checked_mul(12),
+        _ => None,
+    }
+}
+
+/// A recursive descent parser over the tokens of one expression.
+struct Parser<'a> {
+    input: &'a str,
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn expr(&mut self) -> Result<Expr> {
+        let mut expr = self.and_expr()?;
+        while self.keyword("or") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and_expr()?));
+        }
+        Ok(expr)
+    }
+
+    fn and_expr(&mut self) -> Result<Expr> {
+        let mut expr = self.unary()?;
+        while self.keyword("and") {
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        if self.keyword("not") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.peek() == Some(&Token::Open) {
+            self.position += 1;
+            let expr = self.expr()?;
+            if self.peek() != Some(&Token::Close) {
+                return Err(self.error("\")\""));
+            }
+            self.position += 1;
+            return Ok(expr);
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr> {
+        let field = match self.peek() {
+            Some(Token::Word(word)) => FilterField::from_name(word),
+            _ => None,
+        }
+        .ok_or_else(|| self.error("a field (company, position, location, duration, start, or end)"))?;
+        self.position += 1;
+
+        let operator = match self.peek() {
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case("contains") => Some(Operator::Contains),
+            Some(Token::Operator(operator)) => match *operator {
+                "=" => Some(Operator::Eq),
+                "!=" => Some(Operator::Ne),
+                "<" => Some(Operator::Lt),
+                "<=" => Some(Operator::Le),
+                ">" => Some(Operator::Gt),
+                _ => Some(Operator::Ge),
+            },
+            _ => None,
+        }
+        .ok_or_else(|| self.error("an operator"))?;
+        let allowed = if field.is_text() {
+            matches!(operator, Operator::Contains | Operator::Eq | Operator::Ne)
+        } else {
+            operator != Operator::Contains
+        };
+        if !allowed {
+            let expected = if field.is_text() { "contains, =, or !=" } else { "=, !=, <, <=, >, or >=" };
+            return Err(self.error(&format!("{} after {}", expected, field.name())));
+        }
+        self.position += 1;
+
+        let raw = match self.peek() {
+            Some(Token::Word(value)) | Some(Token::Quoted(value)) => Some(value.clone()),
+            _ => None,
+        };
+        let value = match field {
+            _ if field.is_text() => raw.map(|text| Value::Text(text.to_lowercase())),
+            FilterField::Duration => raw.as_deref().and_then(parse_months).map(Value::Months),
+            _ => raw.and_then(|date| match date.to_lowercase().as_str() {
+                "present" | "today" => Some(Value::Date(None)),
+                _ => parse_date(&date)
+                    .ok()
+                    .or_else(|| NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok())
+                    .map(|date| Value::Date(Some(date))),
+            }),
+        };
+        let value = value.ok_or_else(|| {
+            self.error(match field {
+                FilterField::Duration => "a duration such as 12mo or 2y",
+                FilterField::Start | FilterField::End => "a date or \"present\"",
+                _ => "a value",
+            })
+        })?;
+        self.position += 1;
+
+        Ok(Expr::Compare(field, operator, value))
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, token)| token)
+    }
+
+    /// Consumes the next token if it's the given keyword, ignoring case.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword));
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    /// Builds a parse error at the current token.
+    fn error(&self, expected: &str) -> anyhow::Error {
+        match self.tokens.get(self.position) {
+            Some((offset, token)) => {
+                let found = match token {
+                    Token::Word(text) | Token::Quoted(text) => format!("\"{}\"", text),
+                    Token::Operator(operator) => format!("\"{}\"", operator),
+                    Token::Open => "\"(\"".to_string(),
+                    Token::Close => "\")\"".to_string(),
+                };
+                anyhow!(
+                    "Invalid --entry-filter at column {}: expected {}, found {}",
+                    column(self.input, *offset),
+                    expected,
+                    found
+                )
+            }
+            None => anyhow!("Invalid --entry-filter: expected {} at the end of the filter", expected),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::entry;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 6, 15).unwrap()
+    }
+
+    fn matches(filter: &str, history: &WorkHistory) -> bool {
+        EntryFilter::parse(filter).unwrap().matches(history, today())
+    }
+
+    #[test]
+    fn contains_ignores_case() {
+        let acme = entry("Acme Corp", "01/01/2020", "Present");
+        assert!(matches("company contains \"acme\"", &acme));
+        assert!(matches("COMPANY CONTAINS corp", &acme));
+        assert!(!matches("company contains \"Beta\"", &acme));
+        assert!(matches("location contains \"il\"", &acme));
+    }
+
+    #[test]
+    fn equality_compares_the_whole_value() {
+        let acme = entry("Acme Corp", "01/01/2020", "Present");
+        assert!(matches("company = \"acme corp\"", &acme));
+        assert!(matches("position == Engineer", &acme));
+        assert!(!matches("company = Acme", &acme));
+        assert!(matches("company != Acme", &acme));
+    }
+
+    #[test]
+    fn duration_is_compared_in_months() {
+        // 01/2023 to 06/2024 is 17 months
+        let acme = entry("Acme", "01/01/2023", "Present");
+        assert!(matches("duration > 12mo", &acme));
+        assert!(matches("duration >= 17", &acme));
+        assert!(!matches("duration > 17", &acme));
+        assert!(matches("duration < 2y", &acme));
+        assert!(matches("duration <= 17months", &acme));
+        assert!(matches("duration = 17", &acme));
+        assert!(matches("duration != 1y", &acme));
+    }
+
+    #[test]
+    fn dates_are_compared_with_ongoing_jobs_ending_today() {
+        let acme = entry("Acme", "01/15/2020", "Present");
+        let beta = entry("Beta", "06/01/2018", "01/31/2020");
+        assert!(matches("start >= 2020-01-01", &acme));
+        assert!(!matches("start >= 2020-01-01", &beta));
+        assert!(matches("end < \"Feb 2020\"", &beta));
+        assert!(matches("end = present", &acme));
+        assert!(!matches("end = present", &beta));
+    }
+
+    #[test]
+    fn undated_entries_fail_date_and_duration_comparisons() {
+        let undated = entry("Acme", "", "");
+        assert!(!matches("duration >= 0", &undated));
+        assert!(!matches("start < present", &undated));
+        assert!(matches("not end < present", &undated));
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let acme = entry("Acme", "01/01/2023", "Present");
+        assert!(matches("company contains Beta and duration > 1 or company contains Acme", &acme));
+        assert!(!matches("company contains Beta and (duration > 1 or company contains Acme)", &acme));
+        assert!(matches("company contains \"Acme\" and duration > 12mo", &acme));
+        assert!(!matches("not company contains Acme", &acme));
+    }
+
+    #[test]
+    fn parse_errors_name_the_column_and_expectation() {
+        let error = |filter: &str| EntryFilter::parse(filter).unwrap_err().to_string();
+        assert_eq!(
+            error("salary > 5"),
+            "Invalid --entry-filter at column 1: expected a field (company, position, location, duration, start, or end), found \"salary\""
+        );
+        assert_eq!(error("company Acme"), "Invalid --entry-filter at column 9: expected an operator, found \"Acme\"");
+        assert_eq!(
+            error("company > Acme"),
+            "Invalid --entry-filter at column 9: expected contains, =, or != after company, found \">\""
+        );
+        assert_eq!(
+            error("duration > long"),
+            "Invalid --entry-filter at column 12: expected a duration such as 12mo or 2y, found \"long\""
+        );
+        assert_eq!(
+            error("duration > 200000000y"),
+            "Invalid --entry-filter at column 12: expected a duration such as 12mo or 2y, found \"200000000y\""
+        );
+        assert_eq!(error("end <"), "Invalid --entry-filter: expected a date or \"present\" at the end of the filter");
+        assert_eq!(error("(company = Acme"), "Invalid --entry-filter: expected \")\" at the end of the filter");
+        assert_eq!(
+            error("company = Acme Corp"),
+            "Invalid --entry-filter at column 16: expected \"and\", \"or\", or the end of the filter, found \"Corp\""
+        );
+        assert_eq!(error("company = \"Acme"), "Invalid --entry-filter at column 11: unterminated quoted value");
+    }
+}
@@ -0,0 +1,235 @@
+//! Typed errors for problems found in the input data.
+//! 
+//! These carry the row (or columns) and offending value so they can be reported either as
+//! human-readable messages or, with `--error-format json`, as JSON objects on
+//! stderr for tooling to consume.
+
+use serde_json::{json, Value};
+use std::fmt;
+
+/// A problem with a specific row of the input CSV.
+#[derive(Debug, PartialEq)]
+pub enum InputError {
+    /// A date cell could not be parsed
+    DateParse {
+        /// Line number of the row in the input file
+        row: u64,
+        /// The raw cell value
+        value: String,
+    },
+    /// A start date falls after its end date
+    DateOrder {
+        /// Line number of the row in the input file
+        row: u64,
+        /// The raw start date
+        start: String,
+        /// The raw end date
+        end: String,
+    },
+    /// A row's column count differs from the header (strict mode only)
+    ColumnCount {
+        /// Line number of the row in the input file
+        row: u64,
+        /// Number of columns found in the row
+        found: u64,
+        /// Number of columns in the header
+        expected: u64,
+    },
+    /// Two header columns name the same field
+    DuplicateColumn {
+        /// Display name of the field, e.g. "Company"
+        field: String,
+        /// Zero-based index of the first column naming the field
+        first: usize,
+        /// Zero-based index of the second column naming the field
+        second: usize,
+    },
+    /// Columns required with `--require-columns` are missing from the header
+    MissingColumns {
+        /// Display names of the missing fields, e.g. "End Date"
+        columns: Vec<String>,
+    },
+    /// A contact cell holds a malformed email address (`--validate-emails --strict`)
+    InvalidEmail {
+        /// Line number of the row in the input file
+        row: u64,
+        /// Display name of the column, e.g. "Reference Contact"
+        column: String,
+        /// The raw cell value
+        value: String,
+    },
+    /// A row is not valid UTF-8, usually because the file uses a legacy encoding
+    InvalidUtf8 {
+        /// Line number of the row in the input file
+        row: u64,
+        /// Byte offset of the start of the row in the input file
+        byte: u64,
+        /// One-based column containing the invalid bytes
+        column: usize,
+    },
+    /// A date isn't YYYY-MM-DD (`--date-mode iso-strict` only)
+    NonIsoDate {
+        /// Line number of the row in the input file
+        row: u64,
+        /// The raw cell value
+        value: String,
+    },
+}
+
+impl InputError {
+    /// Short machine-readable name of the error kind.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            InputError::DateParse { .. } => "date_parse",
+            InputError::DateOrder { .. } => "date_order",
+            InputError::ColumnCount { .. } => "column_count",
+            InputError::DuplicateColumn { .. } => "duplicate_column",
+            InputError::MissingColumns { .. } => "missing_columns",
+            InputError::InvalidEmail { .. } => "invalid_email",
+            InputError::InvalidUtf8 { .. } => "invalid_utf8",
+            InputError::NonIsoDate { .. } => "non_iso_date",
+        }
+    }
+
+    /// Builds the JSON representation used by `--error-format json`.
+    pub fn to_json(&self) -> Value {
+        match self {
+            InputError::DateParse { row, value } | InputError::NonIsoDate { row, value } => json!({
+                "error": self.kind(),
+                "row": row,
+                "value": value,
+            }),
+            InputError::DateOrder { row, start, end } => json!({
+                "error": self.kind(),
+                "row": row,
+                "start": start,
+                "end": end,
+            }),
+            InputError::ColumnCount { row, found, expected } => json!({
+                "error": self.kind(),
+                "row": row,
+                "found": found,
+                "expected": expected,
+            }),
+            InputError::DuplicateColumn { field, first, second } => json!({
+                "error": self.kind(),
+                "field": field,
+                "columns": [first, second],
+            }),
+            InputError::MissingColumns { columns } => json!({
+                "error": self.kind(),
+                "columns": columns,
+            }),
+            InputError::InvalidEmail { row, column, value } => json!({
+                "error": self.kind(),
+                "row": row,
+                "column": column,
+                "value": value,
+            }),
+            InputError::InvalidUtf8 { row, byte, column } => json!({
+                "error": self.kind(),
+                "row": row,
+                "byte": byte,
+                "column": column,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::DateParse { row, value } => {
+                write!(f, "Row {}: Failed to parse date: {}", row, value)
+            }
+            InputError::DateOrder { row, start, end } => {
+                write!(f, "Row {}: Start date {} is after end date {}", row, start, end)
+            }
+            InputError::ColumnCount { row, found, expected } => {
+                write!(f, "Row {} has {} columns but the header has {}", row, found, expected)
+            }
+            InputError::DuplicateColumn { field, first, second } => write!(
+                f,
+                "The header has two \"{}\" columns, {} and {} (zero-based); remove or rename one",
+                field, first, second
+            ),
+            InputError::MissingColumns { columns } => {
+                write!(f, "The input is missing required columns: {}", columns.join(", "))
+            }
+            InputError::InvalidEmail { row, column, value } => {
+                write!(f, "Row {}: {} \"{}\" is not a valid email address", row, column, value)
+            }
+            InputError::InvalidUtf8 { row, byte, column } => write!(
+                f,
+                "Row {} (byte {}), column {}: The input is not valid UTF-8; \
+                 re-save it as UTF-8, e.g. with `iconv -f windows-1252 -t utf-8`",
+                row, byte, column
+            ),
+            InputError::NonIsoDate { row, value } => write!(
+                f,
+                "Row {}: Date \"{}\" is not a YYYY-MM-DD date, which --date-mode iso-strict requires",
+                row, value
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// Builds the JSON representation of any error for `--error-format json`.
+/// 
+/// Typed input errors keep their fields; anything else is reported with the
+/// kind "error" and its full message chain.
+/// 
+/// # Arguments
+/// * `err` - The error to describe
+/// 
+/// # Returns
+/// * `Value` - The JSON object to print
+pub fn error_to_json(err: &anyhow::Error) -> Value {
+    match err.downcast_ref::<InputError>() {
+        Some(input_error) => input_error.to_json(),
+        None => json!({
+            "error": "error",
+            "message": format!("{:#}", err),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn date_parse_error_serializes_row_and_value() {
+        let err = InputError::DateParse { row: 17, value: "13/40/2020".to_string() };
+        assert_eq!(
+            err.to_json().to_string(),
+            r#"{"error":"date_parse","row":17,"value":"13/40/2020"}"#
+        );
+    }
+
+    #[test]
+    fn untyped_errors_keep_their_message() {
+        let err = anyhow::anyhow!("Input file not found: missing.csv");
+        assert_eq!(
+            error_to_json(&err).to_string(),
+            r#"{"error":"error","message":"Input file not found: missing.csv"}"#
+        );
+    }
+
+    #[test]
+    fn typed_errors_are_found_through_anyhow() {
+        let err = anyhow::Error::new(InputError::ColumnCount { row: 3, found: 9, expected: 8 });
+        assert_eq!(error_to_json(&err)["error"], "column_count");
+    }
+
+    #[test]
+    fn missing_columns_serialize_as_a_list() {
+        let err = InputError::MissingColumns { columns: vec!["Start Date".to_string(), "End Date".to_string()] };
+        assert_eq!(
+            err.to_json().to_string(),
+            r#"{"columns":["Start Date","End Date"],"error":"missing_columns"}"#
+        );
+    }
+}
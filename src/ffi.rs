@@ -0,0 +1,213 @@
+//! A C-compatible interface to the parser, for embedding it in programs
+//! written in other languages through a thin FFI layer.
+//! 
+//! The functions are exported unmangled, and the crate also builds as a
+//! `cdylib`, which exposes them to C as:
+//! 
+//! ```c
+//! typedef struct {
+//!     char *company, *position, *location, *responsibilities;
+//!     uint32_t start_date, end_date;
+//! } WorkHistoryEntry;
+//! typedef struct { WorkHistoryEntry *entries; size_t len; char *error; } WorkHistoryList;
+//! 
+//! WorkHistoryList work_history_parse(const uint8_t *data, size_t len);
+//! void work_history_free(WorkHistoryList list);
+//! ```
+//! 
+//! # Ownership
+//! [`work_history_parse`] only borrows the input for the duration of the call.
+//! Everything it returns (the entry array, each string, and the error) is
+//! owned by this library: the caller may read it until passing the list to
+//! [`work_history_free`] exactly once, and must not free or modify any of it
+//! in another way. After `work_history_free` every pointer in the list is
+//! dangling. Strings are NUL-terminated UTF-8.
+
+use std::any::Any;
+use std::ffi::{c_char, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+
+use anyhow::{anyhow, Result};
+use chrono::Datelike;
+
+use crate::history::WorkHistory;
+use crate::input::read_work_histories;
+use crate::options::Options;
+
+/// One parsed entry, with C strings and dates as YYYYMMDD numbers.
+#[repr(C)]
+#[derive(Debug)]
+pub struct WorkHistoryEntry {
+    /// Name of the employer/company
+    pub company: *mut c_char,
+    /// Job title/position held
+    pub position: *mut c_char,
+    /// Formatted location (City, State)
+    pub location: *mut c_char,
+    /// Description of job responsibilities
+    pub responsibilities: *mut c_char,
+    /// Start date as YYYYMMDD, e.g. 20200115, or 0 for an undated entry
+    pub start_date: u32,
+    /// End date as YYYYMMDD, or 0 for an ongoing job (or an undated entry)
+    pub end_date: u32,
+}
+
+/// The result of [`work_history_parse`]: the entries in input order, or an
+/// error message.
+#[repr(C)]
+#[derive(Debug)]
+pub struct WorkHistoryList {
+    /// The parsed entries, or null on error
+    pub entries: *mut WorkHistoryEntry,
+    /// Number of entries
+    pub len: usize,
+    /// Why parsing failed, or null on success
+    pub error: *mut c_char,
+}
+
+/// Parses CSV input (the same layouts the command-line tool reads, with
+/// default options) into entries in input order.
+/// 
+/// # Arguments
+/// * `data` - The CSV bytes
+/// * `len` - Number of bytes at `data`
+/// 
+/// # Returns
+/// * `WorkHistoryList` - The entries, or a null array with `error` set
+///   (also when the parser panics, which must not unwind into C); release
+///   it with [`work_history_free`]
+/// 
+/// # Safety
+/// `data` must point to `len` readable bytes (or may be null when `len` is 0)
+/// that stay valid for the duration of the call.
+#[no_mangle]
+pub unsafe extern "C" fn work_history_parse(data: *const u8, len: usize) -> WorkHistoryList {
+    let input = if len == 0 { &[][..] } else { slice::from_raw_parts(data, len) };
+
+    match catch_panic(|| read_work_histories(input, &Options::default(), &mut Vec::new())) {
+        Ok(histories) => {
+            let entries: Box<[WorkHistoryEntry]> = histories.iter().map(to_entry).collect();
+            let len = entries.len();
+            WorkHistoryList {
+                entries: Box::into_raw(entries).cast(),
+                len,
+                error: ptr::null_mut(),
+            }
+        }
+        Err(err) => WorkHistoryList {
+            entries: ptr::null_mut(),
+            len: 0,
+            error: c_string(&format!("{:#}", err)),
+        },
+    }
+}
+
+/// Releases everything returned by [`work_history_parse`].
+/// 
+/// # Arguments
+/// * `list` - The list to release
+/// 
+/// # Safety
+/// `list` must have been returned by `work_history_parse` and not freed yet;
+/// none of its pointers may be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn work_history_free(list: WorkHistoryList) {
+    if !list.entries.is_null() {
+        let entries = Box::from_raw(ptr::slice_from_raw_parts_mut(list.entries, list.len));
+        for entry in entries.iter() {
+            for string in [entry.company, entry.position, entry.location, entry.responsibilities] {
+                drop(CString::from_raw(string));
+            }
+        }
+    }
+    if !list.error.is_null() {
+        drop(CString::from_raw(list.error));
+    }
+}
+
+/// Runs the parser, turning a panic into an error so that it doesn't unwind
+/// across the C boundary.
+fn catch_panic<F: FnOnce() -> Result<Vec<WorkHistory>>>(parse: F) -> Result<Vec<WorkHistory>> {
+    panic::catch_unwind(AssertUnwindSafe(parse))
+        .unwrap_or_else(|payload| Err(anyhow!("The parser panicked: {}", panic_message(&*payload))))
+}
+
+/// The message a panic was raised with, if it was a string.
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
+
+/// Converts an entry to its C representation, allocating its strings.
+fn to_entry(history: &WorkHistory) -> WorkHistoryEntry {
+    let ymd = |date: chrono::NaiveDate| date.year() as u32 * 10000 + date.month() * 100 + date.day();
+    WorkHistoryEntry {
+        company: c_string(&history.company),
+        position: c_string(&history.position),
+        location: c_string(&history.location),
+        responsibilities: c_string(&history.responsibilities),
+        start_date: history.start_date.map_or(0, ymd),
+        end_date: history.end_date.map_or(0, ymd),
+    }
+}
+
+/// Allocates a C string, dropping any NUL characters that C can't represent.
+fn c_string(text: &str) -> *mut c_char {
+    CString::new(text.replace('\0', "")).unwrap_or_default().into_raw()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::HEADER;
+    use std::ffi::CStr;
+
+    #[test]
+    fn parse_returns_c_entries_in_input_order() {
+        let data = format!(
+            "{}Acme,Engineer,01/15/2020,Present,\"Springfield, IL\",Jane,Build,\nBeta,Analyst,06/01/2018,01/31/2020,\"Austin, TX\",Bob,Count,\n",
+            HEADER
+        );
+
+        unsafe {
+            let list = work_history_parse(data.as_ptr(), data.len());
+            assert!(list.error.is_null());
+            let entries = slice::from_raw_parts(list.entries, list.len);
+            assert_eq!(entries.len(), 2);
+            assert_eq!(CStr::from_ptr(entries[0].company).to_str(), Ok("Acme"));
+            assert_eq!(CStr::from_ptr(entries[0].location).to_str(), Ok("Springfield, IL"));
+            assert_eq!((entries[0].start_date, entries[0].end_date), (20200115, 0));
+            assert_eq!(CStr::from_ptr(entries[1].position).to_str(), Ok("Analyst"));
+            assert_eq!((entries[1].start_date, entries[1].end_date), (20180601, 20200131));
+            work_history_free(list);
+        }
+    }
+
+    #[test]
+    fn parse_errors_are_returned_as_a_message() {
+        let data = format!("{}Acme,Engineer,13/40/2020,Present,\"Springfield, IL\",Jane,Build,\n", HEADER);
+
+        unsafe {
+            let list = work_history_parse(data.as_ptr(), data.len());
+            assert!(list.entries.is_null());
+            assert_eq!(list.len, 0);
+            assert_eq!(CStr::from_ptr(list.error).to_str(), Ok("Row 2: Failed to parse date: 13/40/2020"));
+            work_history_free(list);
+        }
+    }
+
+    #[test]
+    fn parser_panics_are_returned_as_errors() {
+        let err = catch_panic(|| panic!("index out of bounds")).unwrap_err();
+        assert_eq!(err.to_string(), "The parser panicked: index out of bounds");
+
+        let row = 7;
+        let err = catch_panic(|| panic!("bad row {}", row)).unwrap_err();
+        assert_eq!(err.to_string(), "The parser panicked: bad row 7");
+    }
+}
@@ -0,0 +1,681 @@
+//! Filters that narrow down the parsed entries, and orderings for them.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{Datelike, NaiveDate};
+use std::cmp::Reverse;
+use std::fs;
+use std::path::Path;
+
+use crate::history::{entry_count, WorkHistory};
+use crate::options::{Options, SortBy, UndatedPlacement};
+
+/// Keeps only the entries from the first job at the given company onward.
+/// 
+/// Entries are ordered by start date (oldest first, with undated entries
+/// before all dated ones) and everything before the first entry whose
+/// company or display name matches `company` (ignoring case and
+/// surrounding whitespace) is dropped.
+/// 
+/// # Arguments
+/// * `work_histories` - The parsed entries
+/// * `company` - Name of the company to start from
+/// 
+/// # Returns
+/// * `Result<Vec<WorkHistory>>` - The remaining entries, sorted by start date
+/// 
+/// # Errors
+/// Returns an error if no entry matches `company`
+pub fn filter_since_company(mut work_histories: Vec<WorkHistory>, company: &str) -> Result<Vec<WorkHistory>> {
+    work_histories.sort_by_key(|history| history.start_date);
+
+    let wanted = company.trim().to_lowercase();
+    let first = work_histories
+        .iter()
+        .position(|history| {
+            history.company.trim().to_lowercase() == wanted
+                || history.display_company().trim().to_lowercase() == wanted
+        })
+        .ok_or_else(|| anyhow!("Company not found in work history: {}", company))?;
+
+    Ok(work_histories.split_off(first))
+}
+
+/// Keeps only entries for jobs that are still ongoing (not undated ones).
+/// 
+/// # Arguments
+/// * `work_histories` - The parsed entries
+/// 
+/// # Returns
+/// * `Result<Vec<WorkHistory>>` - The ongoing entries
+/// 
+/// # Errors
+/// Returns an error if no entry is ongoing
+pub fn filter_current(mut work_histories: Vec<WorkHistory>) -> Result<Vec<WorkHistory>> {
+    work_histories.retain(|history| history.end_date.is_none() && !history.is_undated());
+
+    if work_histories.is_empty() {
+        return Err(anyhow!(
+            "No current jobs found; an ongoing job needs an empty or \"Present\" end date"
+        ));
+    }
+
+    Ok(work_histories)
+}
+
+/// Drops entries whose tenure is shorter than a number of months.
+/// 
+/// Tenure is counted in whole calendar months from the start month to the
+/// end month (see [`WorkHistory::month_span`]); an ongoing job is measured
+/// until `today`. Undated entries have no known tenure and are kept.
+/// 
+/// # Arguments
+/// * `work_histories` - The parsed entries
+/// * `min_months` - The shortest tenure kept
+/// * `today` - The date an ongoing job is measured to
+/// 
+/// # Returns
+/// * `Vec<WorkHistory>` - The entries lasting at least `min_months`
+pub fn filter_min_tenure(mut work_histories: Vec<WorkHistory>, min_months: usize, today: NaiveDate) -> Vec<WorkHistory> {
+    work_histories.retain(|history| {
+        history
+            .tenure_months(today)
+            .is_none_or(|months| months as usize >= min_months)
+    });
+    work_histories
+}
+
+/// Reads an exclude list for `--exclude-file`: one company name per line,
+/// with blank lines and lines starting with `#` skipped.
+/// 
+/// # Arguments
+/// * `path` - The file listing the companies to drop
+/// 
+/// # Returns
+/// * `Result<Vec<String>>` - The trimmed company names
+/// 
+/// # Errors
+/// Returns an error if the file can't be read
+pub fn read_exclude_list(path: &Path) -> Result<Vec<String>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read exclude file: {}", path.display()))?;
+    Ok(contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect())
+}
+
+/// Drops the entries at any of the given companies, matching the company or
+/// display name ignoring case and surrounding whitespace.
+/// 
+/// # Arguments
+/// * `work_histories` - The parsed entries
+/// * `companies` - Names of the companies to drop
+/// 
+/// # Returns
+/// * `Vec<WorkHistory>` - The entries at other companies
+pub fn filter_excluded(mut work_histories: Vec<WorkHistory>, companies: &[String]) -> Vec<WorkHistory> {
+    let excluded: Vec<String> = companies.iter().map(|company| company.trim().to_lowercase()).collect();
+    work_histories.retain(|history| {
+        let names = [history.company.trim().to_lowercase(), history.display_company().trim().to_lowercase()];
+        !names.iter().any(|name| excluded.contains(name))
+    });
+    work_histories
+}
+
+/// Applies every filter requested in the options, in order: `exclude_file`,
+/// `since_company`, `only_current`, then `min_tenure`, and then checks that
+/// at least `min_entries` remain.
+/// 
+/// # Arguments
+/// * `work_histories` - The parsed entries
+/// * `options` - The filtering options
+/// * `today` - The date ongoing jobs are measured to
+/// * `notes` - Collects reports about dropped entries, e.g. for `exclude_file`, `min_tenure`, and `entry_filter`
+/// 
+/// # Returns
+/// * `Result<Vec<WorkHistory>>` - The remaining entries
+/// 
+/// # Errors
+/// Returns an error if the exclude file can't be read, a filter finds
+/// nothing to keep (see the individual filters), or fewer than
+/// `min_entries` entries remain
+pub fn apply_filters(
+    mut work_histories: Vec<WorkHistory>,
+    options: &Options,
+    today: NaiveDate,
+    notes: &mut Vec<String>,
+) -> Result<Vec<WorkHistory>> {
+    // Drop the companies in the exclude list
+    if let Some(path) = &options.exclude_file {
+        let before = work_histories.len();
+        work_histories = filter_excluded(work_histories, &read_exclude_list(path)?);
+        notes.push(format!(
+            "Excluded {} listed in {}",
+            entry_count(before - work_histories.len()),
+            path.display()
+        ));
+    }
+
+    // Drop jobs before the requested starting company
+    if let Some(company) = &options.since_company {
+        work_histories = filter_since_company(work_histories, company)?;
+    }
+
+    // Keep only ongoing jobs
+    if options.only_current {
+        work_histories = filter_current(work_histories)?;
+    }
+
+    // Drop short stints
+    if let Some(min_months) = options.min_tenure {
+        let before = work_histories.len();
+        work_histories = filter_min_tenure(work_histories, min_months, today);
+        notes.push(format!(
+            "Dropped {} shorter than {} months",
+            entry_count(before - work_histories.len()),
+            min_months
+        ));
+    }
+
+    // Keep the entries matching the filter expression
+    if let Some(filter) = &options.entry_filter {
+        let before = work_histories.len();
+        work_histories.retain(|history| filter.matches(history, today));
+        notes.push(format!(
+            "Dropped {} not matching --entry-filter",
+            entry_count(before - work_histories.len())
+        ));
+    }
+
+    // Catch an empty or over-filtered input
+    if let Some(min_entries) = options.min_entries {
+        if work_histories.len() < min_entries {
+            let remain = if work_histories.len() == 1 { "remains" } else { "remain" };
+            return Err(anyhow!(
+                "Only {} {} after filtering, fewer than --min-entries {}",
+                entry_count(work_histories.len()),
+                remain,
+                min_entries
+            ));
+        }
+    }
+
+    Ok(work_histories)
+}
+
+/// Orders entries by end year, newest first, and alphabetically by company
+/// (ignoring case) within each year.
+/// 
+/// Ongoing jobs count as ending after every finished job. The sort is
+/// stable, so entries at the same company in the same year keep their
+/// current relative order.
+/// 
+/// # Arguments
+/// * `work_histories` - The entries to reorder in place
+pub fn sort_company_within_year(work_histories: &mut [WorkHistory]) {
+    work_histories.sort_by_cached_key(|history| {
+        let year = history.end_date.map_or(i32::MAX, |end| end.year());
+        (Reverse(year), history.company.to_lowercase())
+    });
+}
+
+/// Combines consecutive entries at the same company (ignoring case and
+/// surrounding whitespace) into one entry, such as two roles around a
+/// promotion.
+/// 
+/// The merged entry spans from the earliest start to the latest end date
+/// (ongoing if either role is), joins the distinct positions and the
+/// non-empty responsibilities with "; " in their current order (or with
+/// `by_start` in order of start date, earliest first), and keeps the other
+/// fields of the first entry. Undated entries are never merged.
+/// 
+/// # Arguments
+/// * `work_histories` - The entries, in output order
+/// * `by_start` - Whether to join the roles in start date order, for `--position-sort-within-company`
+/// 
+/// # Returns
+/// * `Vec<WorkHistory>` - The entries with adjacent same-company runs merged
+pub fn merge_adjacent(work_histories: Vec<WorkHistory>, by_start: bool) -> Vec<WorkHistory> {
+    let same_company = |a: &WorkHistory, b: &WorkHistory| {
+        !a.is_undated() && !b.is_undated() && a.company.trim().eq_ignore_ascii_case(b.company.trim())
+    };
+
+    let mut runs: Vec<Vec<WorkHistory>> = Vec::with_capacity(work_histories.len());
+    for history in work_histories {
+        match runs.last_mut().filter(|run| same_company(&run[0], &history)) {
+            Some(run) => run.push(history),
+            None => runs.push(vec![history]),
+        }
+    }
+    runs.into_iter().map(|run| merge_run(run, by_start)).collect()
+}
+
+/// Merges one run of same-company entries, see [`merge_adjacent`].
+fn merge_run(run: Vec<WorkHistory>, by_start: bool) -> WorkHistory {
+    let mut joined: Vec<&WorkHistory> = run.iter().collect();
+    if by_start {
+        joined.sort_by_key(|history| history.start_date);
+    }
+    let mut position = String::new();
+    let mut responsibilities = String::new();
+    for history in joined {
+        if !position.split("; ").any(|known| known == history.position) {
+            position = join_non_empty(&position, &history.position);
+        }
+        responsibilities = join_non_empty(&responsibilities, &history.responsibilities);
+    }
+
+    let mut run = run.into_iter();
+    let mut merged = run.next().expect("runs are never empty");
+    for history in run {
+        merged.start_date = merged.start_date.min(history.start_date);
+        merged.end_date = merged.end_date.zip(history.end_date).map(|(a, b)| a.max(b));
+        for skill in history.skills {
+            if !merged.skills.iter().any(|known| known.eq_ignore_ascii_case(&skill)) {
+                merged.skills.push(skill);
+            }
+        }
+        merged.changes.extend(history.changes);
+    }
+    merged.position = position;
+    merged.responsibilities = responsibilities;
+    merged
+}
+
+/// Joins two texts with "; ", leaving out an empty one.
+fn join_non_empty(first: &str, second: &str) -> String {
+    match (first.trim().is_empty(), second.trim().is_empty()) {
+        (_, true) => first.to_string(),
+        (true, false) => second.to_string(),
+        (false, false) => format!("{}; {}", first, second),
+    }
+}
+
+/// Orders entries by tenure, longest first, with ongoing jobs measured until
+/// `today`. The sort is stable, so entries of equal tenure keep their order.
+/// 
+/// # Arguments
+/// * `work_histories` - The entries to reorder in place
+/// * `today` - The date ongoing jobs are measured to
+pub fn sort_by_duration(work_histories: &mut [WorkHistory], today: NaiveDate) {
+    work_histories.sort_by_key(|history| Reverse(history.tenure_months(today)));
+}
+
+/// Restores the order the entries were read in. Every later sort is
+/// stable, so doing this first makes input order the final tiebreaker.
+/// 
+/// # Arguments
+/// * `work_histories` - The entries to reorder in place
+pub fn restore_input_order(work_histories: &mut [WorkHistory]) {
+    work_histories.sort_by_key(|history| history.source_index);
+}
+
+/// Puts the entries in output order: most recent end date first (ongoing
+/// jobs at the top), reordered by `--sort-by` and `--company-sort-within-year`,
+/// with undated entries placed per `--undated` and, with `--merge-adjacent`,
+/// neighbouring entries at the same company merged. With
+/// `--keep-order-stable`, ties are broken by input order.
+/// 
+/// # Arguments
+/// * `work_histories` - The filtered entries
+/// * `options` - The sorting options
+/// 
+/// # Returns
+/// * `Vec<WorkHistory>` - The entries in output order
+pub fn sort_entries(mut work_histories: Vec<WorkHistory>, options: &Options) -> Vec<WorkHistory> {
+    // Break ties by input order rather than by whatever order filtering left
+    if options.keep_order_stable {
+        restore_input_order(&mut work_histories);
+    }
+
+    work_histories.sort_by_key(|history| Reverse(history.end_date.unwrap_or(NaiveDate::MAX)));
+    if options.sort_by == SortBy::Duration {
+        sort_by_duration(&mut work_histories, options.today());
+    }
+    if options.company_sort_within_year {
+        sort_company_within_year(&mut work_histories);
+    }
+    place_undated(&mut work_histories, options.undated);
+    if options.merge_adjacent {
+        work_histories = merge_adjacent(work_histories, options.position_sort_within_company);
+    }
+
+    work_histories
+}
+
+/// Moves undated entries to the top or bottom, keeping the order of the
+/// dated entries and of the undated entries among themselves.
+/// 
+/// # Arguments
+/// * `work_histories` - The sorted entries to adjust in place
+/// * `placement` - Where the undated entries go
+pub fn place_undated(work_histories: &mut [WorkHistory], placement: UndatedPlacement) {
+    match placement {
+        UndatedPlacement::Bottom => work_histories.sort_by_key(|history| history.is_undated()),
+        UndatedPlacement::Top => work_histories.sort_by_key(|history| !history.is_undated()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::entry;
+    use crate::entry_filter::EntryFilter;
+
+    #[test]
+    fn filter_since_company_drops_earlier_jobs() {
+        let histories = vec![
+            entry("Later Co", "01/01/2022", "01/01/2023"),
+            entry("Old Co", "01/01/2015", "01/01/2018"),
+            entry("Acme", "01/01/2018", "01/01/2020"),
+            entry("Between Inc", "01/01/2020", "01/01/2022"),
+            entry("Acme", "01/01/2023", "01/01/2024"),
+        ];
+
+        let kept = filter_since_company(histories, " acme ").unwrap();
+        let companies: Vec<&str> = kept.iter().map(|h| h.company.as_str()).collect();
+        assert_eq!(companies, ["Acme", "Between Inc", "Later Co", "Acme"]);
+    }
+
+    #[test]
+    fn filter_since_company_errors_when_missing() {
+        let histories = vec![entry("Old Co", "01/01/2015", "01/01/2018")];
+        let err = filter_since_company(histories, "Acme").unwrap_err();
+        assert!(err.to_string().contains("Acme"));
+    }
+
+    #[test]
+    fn filter_current_keeps_only_ongoing_jobs() {
+        let histories = vec![
+            entry("Old Co", "01/01/2015", "01/01/2018"),
+            entry("Acme", "01/01/2018", ""),
+            entry("Side Gig", "01/01/2019", "Present"),
+        ];
+
+        let kept = filter_current(histories).unwrap();
+        let companies: Vec<&str> = kept.iter().map(|h| h.company.as_str()).collect();
+        assert_eq!(companies, ["Acme", "Side Gig"]);
+    }
+
+    #[test]
+    fn filter_current_errors_when_none_ongoing() {
+        let histories = vec![entry("Old Co", "01/01/2015", "01/01/2018")];
+        assert!(filter_current(histories).unwrap_err().to_string().contains("No current jobs"));
+    }
+
+    #[test]
+    fn filter_min_tenure_keeps_entries_at_the_threshold() {
+        let today = NaiveDate::from_ymd_opt(2024, 6, 15).unwrap();
+        let histories = vec![
+            entry("Short", "01/01/2020", "06/30/2020"),
+            entry("Exact", "01/01/2021", "07/01/2021"),
+            entry("Long", "01/01/2015", "01/01/2018"),
+            entry("Ongoing", "01/01/2024", "Present"),
+        ];
+
+        let kept = filter_min_tenure(histories, 6, today);
+        let companies: Vec<&str> = kept.iter().map(|h| h.company.as_str()).collect();
+        assert_eq!(companies, ["Exact", "Long"]);
+    }
+
+    #[test]
+    fn sort_company_within_year_groups_by_year_then_company() {
+        let mut histories = vec![
+            entry("Zeta", "01/01/2022", "11/01/2022"),
+            entry("acme", "01/01/2021", "03/01/2022"),
+            entry("Beta", "01/01/2020", "06/01/2021"),
+            entry("Now Co", "01/01/2023", "Present"),
+            entry("Alpha", "01/01/2020", "02/01/2021"),
+        ];
+
+        sort_company_within_year(&mut histories);
+        let companies: Vec<&str> = histories.iter().map(|h| h.company.as_str()).collect();
+        assert_eq!(companies, ["Now Co", "acme", "Zeta", "Alpha", "Beta"]);
+    }
+
+    #[test]
+    fn apply_filters_combines_the_requested_filters() {
+        let today = NaiveDate::from_ymd_opt(2024, 6, 15).unwrap();
+        let histories = || vec![
+            entry("Old Co", "01/01/2015", "01/01/2018"),
+            entry("Acme", "01/01/2018", "Present"),
+            entry("Side Gig", "01/01/2019", "01/01/2020"),
+            entry("Short", "03/01/2024", "Present"),
+        ];
+
+        let options = Options { since_company: Some("Acme".to_string()), ..Options::default() };
+        assert_eq!(apply_filters(histories(), &options, today, &mut Vec::new()).unwrap().len(), 3);
+
+        let options = Options { only_current: true, min_tenure: Some(6), ..Options::default() };
+        let mut notes = Vec::new();
+        let kept = apply_filters(histories(), &options, today, &mut notes).unwrap();
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].company, "Acme");
+        assert_eq!(notes, ["Dropped 1 entry shorter than 6 months"]);
+    }
+
+    #[test]
+    fn undated_entries_are_placed_by_policy() {
+        let mut histories = vec![
+            entry("Volunteer", "", ""),
+            entry("Acme", "01/01/2020", "Present"),
+            entry("Freelance", "", ""),
+            entry("Old Co", "01/01/2015", "01/01/2018"),
+        ];
+
+        place_undated(&mut histories, UndatedPlacement::Bottom);
+        let companies: Vec<&str> = histories.iter().map(|h| h.company.as_str()).collect();
+        assert_eq!(companies, ["Acme", "Old Co", "Volunteer", "Freelance"]);
+
+        place_undated(&mut histories, UndatedPlacement::Top);
+        let companies: Vec<&str> = histories.iter().map(|h| h.company.as_str()).collect();
+        assert_eq!(companies, ["Volunteer", "Freelance", "Acme", "Old Co"]);
+    }
+
+    #[test]
+    fn undated_entries_are_not_current() {
+        let histories = vec![entry("Volunteer", "", ""), entry("Acme", "01/01/2020", "Present")];
+        let kept = filter_current(histories).unwrap();
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].company, "Acme");
+    }
+
+    #[test]
+    fn merge_adjacent_combines_consecutive_roles_at_one_company() {
+        let mut senior = entry("Acme", "01/01/2021", "Present");
+        senior.position = "Senior Engineer".to_string();
+        senior.responsibilities = "Led the team".to_string();
+        let mut junior = entry("acme ", "01/01/2018", "01/01/2021");
+        junior.responsibilities = "Wrote code".to_string();
+        let histories = vec![senior, junior, entry("Other", "01/01/2016", "01/01/2018")];
+
+        let merged = merge_adjacent(histories, false);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].company, "Acme");
+        assert_eq!(merged[0].position, "Senior Engineer; Engineer");
+        assert_eq!(merged[0].responsibilities, "Led the team; Wrote code");
+        assert_eq!(merged[0].start_date, NaiveDate::from_ymd_opt(2018, 1, 1));
+        assert_eq!(merged[0].end_date, None);
+        assert_eq!(merged[1].company, "Other");
+    }
+
+    #[test]
+    fn merge_adjacent_keeps_separated_and_undated_entries() {
+        let histories = vec![
+            entry("Acme", "01/01/2022", "Present"),
+            entry("Other", "01/01/2020", "01/01/2022"),
+            entry("Acme", "01/01/2018", "01/01/2020"),
+            entry("Food Bank", "", ""),
+            entry("Food Bank", "", ""),
+        ];
+
+        let merged = merge_adjacent(histories, false);
+        let companies: Vec<&str> = merged.iter().map(|h| h.company.as_str()).collect();
+        assert_eq!(companies, ["Acme", "Other", "Acme", "Food Bank", "Food Bank"]);
+
+        let merged = merge_adjacent(vec![
+            entry("Acme", "01/01/2020", "01/01/2021"),
+            entry("Acme", "01/01/2019", "01/01/2020"),
+        ], false);
+        assert_eq!(merged[0].position, "Engineer");
+        assert_eq!(merged[0].responsibilities, "Build things; Build things");
+    }
+
+    #[test]
+    fn sort_by_duration_measures_ongoing_jobs_until_today() {
+        let histories = || vec![
+            entry("Two Years", "01/01/2016", "01/01/2018"),
+            entry("Ongoing", "01/01/2020", "Present"),
+            entry("Three Years", "01/01/2010", "01/01/2013"),
+        ];
+
+        let mut early = histories();
+        sort_by_duration(&mut early, NaiveDate::from_ymd_opt(2021, 1, 1).unwrap());
+        let companies: Vec<&str> = early.iter().map(|h| h.company.as_str()).collect();
+        assert_eq!(companies, ["Three Years", "Two Years", "Ongoing"]);
+
+        let mut later = histories();
+        sort_by_duration(&mut later, NaiveDate::from_ymd_opt(2024, 6, 1).unwrap());
+        let companies: Vec<&str> = later.iter().map(|h| h.company.as_str()).collect();
+        assert_eq!(companies, ["Ongoing", "Three Years", "Two Years"]);
+    }
+
+    #[test]
+    fn restore_input_order_breaks_ties_by_source_index() {
+        let ordered = |names: [&str; 3]| {
+            let mut histories: Vec<_> = names
+                .iter()
+                .zip(["01/01/2019", "01/01/2015", "01/01/2017"])
+                .enumerate()
+                .map(|(index, (name, start))| WorkHistory { source_index: index, ..entry(name, start, "01/01/2022") })
+                .collect();
+            // --since-company leaves the entries ordered by start date
+            histories.sort_by_key(|history| history.start_date);
+            restore_input_order(&mut histories);
+            histories.sort_by_key(|history| Reverse(history.end_date));
+            histories.into_iter().map(|history| history.company).collect::<Vec<_>>()
+        };
+
+        assert_eq!(ordered(["Acme", "Beta", "Gamma"]), ["Acme", "Beta", "Gamma"]);
+        assert_eq!(ordered(["Gamma", "Acme", "Beta"]), ["Gamma", "Acme", "Beta"]);
+    }
+
+    #[test]
+    fn sort_entries_ignores_row_order_with_keep_order_stable() {
+        let histories = || {
+            ["Acme", "Beta", "Gamma"]
+                .iter()
+                .enumerate()
+                .map(|(index, name)| WorkHistory { source_index: index, ..entry(name, "01/01/2018", "01/01/2022") })
+                .collect::<Vec<_>>()
+        };
+        let sorted = |mut histories: Vec<WorkHistory>, reverse: bool, options: &Options| {
+            if reverse {
+                histories.reverse();
+            }
+            sort_entries(histories, options).into_iter().map(|history| history.company).collect::<Vec<_>>()
+        };
+
+        let options = Options::default();
+        assert_ne!(sorted(histories(), false, &options), sorted(histories(), true, &options));
+
+        let options = Options { keep_order_stable: true, ..Options::default() };
+        assert_eq!(sorted(histories(), true, &options), ["Acme", "Beta", "Gamma"]);
+        assert_eq!(sorted(histories(), false, &options), ["Acme", "Beta", "Gamma"]);
+    }
+
+    #[test]
+    fn filter_excluded_drops_listed_companies_ignoring_case() {
+        let mut renamed = entry("Alphabet Inc.", "01/01/2016", "01/01/2018");
+        renamed.display_name = Some("Google".to_string());
+        let histories = vec![
+            entry("Acme", "01/01/2020", "Present"),
+            entry("Initech ", "01/01/2018", "01/01/2020"),
+            renamed,
+            entry("Globex", "01/01/2014", "01/01/2016"),
+        ];
+
+        let excluded = ["initech".to_string(), " GOOGLE ".to_string(), "Umbrella".to_string()];
+        let kept = filter_excluded(histories, &excluded);
+        let companies: Vec<&str> = kept.iter().map(|h| h.company.as_str()).collect();
+        assert_eq!(companies, ["Acme", "Globex"]);
+    }
+
+    #[test]
+    fn exclude_file_is_applied_and_reported() {
+        let path = std::env::temp_dir().join(format!("work_history_exclude_{}.txt", std::process::id()));
+        std::fs::write(&path, "# Competitors\nInitech\n\nUmbrella Corp\n").unwrap();
+        let histories = vec![entry("Acme", "01/01/2020", "Present"), entry("initech", "01/01/2018", "01/01/2020")];
+        let options = Options { exclude_file: Some(path.clone()), ..Options::default() };
+
+        let mut notes = Vec::new();
+        let kept = apply_filters(histories, &options, NaiveDate::from_ymd_opt(2024, 6, 15).unwrap(), &mut notes).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(kept.len(), 1);
+        assert_eq!(notes, [format!("Excluded 1 entry listed in {}", path.display())]);
+    }
+
+    #[test]
+    fn min_entries_counts_what_remains_after_filtering() {
+        let today = NaiveDate::from_ymd_opt(2024, 6, 15).unwrap();
+        let histories = || vec![
+            entry("Acme", "01/01/2018", "Present"),
+            entry("Short", "03/01/2024", "Present"),
+        ];
+
+        let options = Options { min_entries: Some(2), ..Options::default() };
+        assert_eq!(apply_filters(histories(), &options, today, &mut Vec::new()).unwrap().len(), 2);
+
+        let options = Options { min_tenure: Some(6), min_entries: Some(2), ..Options::default() };
+        let err = apply_filters(histories(), &options, today, &mut Vec::new()).unwrap_err();
+        assert_eq!(err.to_string(), "Only 1 entry remains after filtering, fewer than --min-entries 2");
+    }
+
+    #[test]
+    fn entry_filter_keeps_matching_entries_and_is_reported() {
+        let today = NaiveDate::from_ymd_opt(2024, 6, 15).unwrap();
+        let histories = vec![
+            entry("Acme", "01/01/2018", "Present"),
+            entry("Acme Labs", "03/01/2024", "Present"),
+            entry("Beta", "01/01/2016", "01/01/2018"),
+        ];
+        let options = Options {
+            entry_filter: Some(EntryFilter::parse("company contains acme and duration > 12mo").unwrap()),
+            ..Options::default()
+        };
+
+        let mut notes = Vec::new();
+        let kept = apply_filters(histories, &options, today, &mut notes).unwrap();
+        assert_eq!(kept.iter().map(|history| history.company.as_str()).collect::<Vec<_>>(), ["Acme"]);
+        assert_eq!(notes, ["Dropped 2 entries not matching --entry-filter"]);
+    }
+
+    #[test]
+    fn position_sort_within_company_joins_roles_earliest_first() {
+        let role = |position: &str, start: &str, end: &str| {
+            let mut history = entry("Acme", start, end);
+            history.position = position.to_string();
+            history.responsibilities = format!("{} work", position);
+            history
+        };
+        // Out of order promotion rows, as left by --sort-by duration
+        let histories = || vec![
+            role("Senior Engineer", "01/01/2020", "01/01/2022"),
+            role("Staff Engineer", "01/01/2022", "Present"),
+            role("Engineer", "01/01/2017", "01/01/2020"),
+        ];
+
+        let merged = merge_adjacent(histories(), false);
+        assert_eq!(merged[0].position, "Senior Engineer; Staff Engineer; Engineer");
+
+        let merged = merge_adjacent(histories(), true);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].position, "Engineer; Senior Engineer; Staff Engineer");
+        assert_eq!(merged[0].responsibilities, "Engineer work; Senior Engineer work; Staff Engineer work");
+        assert_eq!(merged[0].start_date, NaiveDate::from_ymd_opt(2017, 1, 1));
+        assert_eq!(merged[0].end_date, None);
+    }
+}
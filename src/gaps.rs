@@ -0,0 +1,117 @@
+//! Employment gaps: stretches of time between jobs that no entry covers.
+
+use chrono::NaiveDate;
+
+use crate::history::{format_date, WorkHistory};
+
+/// A stretch of whole months between the end of one job and the start of the
+/// next, with no other job in between.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gap {
+    /// Company of the job that ended before the gap
+    pub after: String,
+    /// Date the job before the gap ended
+    pub from: NaiveDate,
+    /// Company of the job that started after the gap
+    pub before: String,
+    /// Date the job after the gap started
+    pub to: NaiveDate,
+    /// Length of the gap in whole months
+    pub months: i32,
+}
+
+impl Gap {
+    /// Describes the gap, e.g. "8-month gap between Acme (ended 01/01/2020)
+    /// and Beta (started 09/01/2020)".
+    pub fn describe(&self) -> String {
+        format!(
+            "{}-month gap between {} (ended {}) and {} (started {})",
+            self.months,
+            self.after,
+            format_date(self.from),
+            self.before,
+            format_date(self.to)
+        )
+    }
+}
+
+/// Finds the gaps between jobs, oldest first.
+/// 
+/// Jobs are taken in start date order and overlapping jobs cover each other,
+/// so a gap is only found where no job at all was held for a whole month.
+/// Ongoing jobs run until `today`; undated entries are ignored.
+/// 
+/// # Arguments
+/// * `work_histories` - The parsed entries, in any order
+/// * `today` - The date ongoing jobs are measured to
+/// 
+/// # Returns
+/// * `Vec<Gap>` - Every gap of at least one month
+pub fn find_gaps(work_histories: &[WorkHistory], today: NaiveDate) -> Vec<Gap> {
+    let mut dated: Vec<&WorkHistory> = work_histories.iter().filter(|history| !history.is_undated()).collect();
+    dated.sort_by_key(|history| history.start_date);
+
+    let mut gaps = Vec::new();
+    // The job covering the latest month so far, and the month after it
+    let mut covered: Option<(&WorkHistory, i32)> = None;
+
+    for history in dated {
+        let Some((start, end)) = history.month_span(today) else {
+            continue;
+        };
+        match covered {
+            Some((latest, covered_end)) if start > covered_end => {
+                gaps.push(Gap {
+                    after: latest.display_company().to_string(),
+                    from: latest.end_date.unwrap_or(today),
+                    before: history.display_company().to_string(),
+                    to: history.start_date.unwrap_or(today),
+                    months: start - covered_end,
+                });
+                covered = Some((history, end));
+            }
+            Some((_, covered_end)) if end <= covered_end => {}
+            _ => covered = Some((history, end)),
+        }
+    }
+
+    gaps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::entry;
+
+    #[test]
+    fn gaps_are_found_between_jobs_in_start_order() {
+        let today = NaiveDate::from_ymd_opt(2024, 6, 15).unwrap();
+        let histories = vec![
+            entry("Gamma", "01/01/2023", "Present"),
+            entry("Acme", "01/01/2015", "01/01/2020"),
+            entry("Beta", "09/01/2020", "06/01/2022"),
+            entry("Volunteer", "", ""),
+        ];
+
+        let gaps = find_gaps(&histories, today);
+        assert_eq!(
+            gaps.iter().map(Gap::describe).collect::<Vec<_>>(),
+            [
+                "8-month gap between Acme (ended 01/01/2020) and Beta (started 09/01/2020)",
+                "7-month gap between Beta (ended 06/01/2022) and Gamma (started 01/01/2023)",
+            ]
+        );
+    }
+
+    #[test]
+    fn overlapping_and_back_to_back_jobs_leave_no_gap() {
+        let today = NaiveDate::from_ymd_opt(2024, 6, 15).unwrap();
+        let histories = vec![
+            entry("Long", "01/01/2015", "01/01/2022"),
+            entry("Side Gig", "01/01/2016", "01/01/2017"),
+            entry("Next", "01/15/2022", "Present"),
+        ];
+
+        assert!(find_gaps(&histories, today).is_empty());
+    }
+}
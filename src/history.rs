@@ -0,0 +1,314 @@
+//! The parsed work history entry and how its dates are rendered.
+
+use chrono::{Datelike, NaiveDate};
+use serde::Serialize;
+
+/// Represents a single work history entry with parsed and formatted fields.
+/// 
+/// This struct contains the essential information extracted from a CSV record,
+/// with dates parsed into `NaiveDate` for proper chronological sorting and
+/// formatting. It serializes with ISO 8601 dates and `null` for missing
+/// optional fields (and for the end date of an ongoing job).
+#[derive(Debug, Serialize)]
+pub struct WorkHistory {
+    /// Name of the employer/company
+    pub company: String,
+    /// Job title/position held
+    pub position: String,
+    /// Employment start date, or `None` for an undated entry (one with
+    /// neither a start nor an end date, such as volunteer work)
+    pub start_date: Option<NaiveDate>,
+    /// Employment end date, or `None` for a job that is still ongoing
+    pub end_date: Option<NaiveDate>,
+    /// Department within the company, if the input has one
+    pub department: Option<String>,
+    /// Formatted location (City, State)
+    pub location: String,
+    /// Description of job responsibilities
+    pub responsibilities: String,
+    /// Salary as written in the input, if the input has one
+    pub salary: Option<String>,
+    /// Skill tags from the optional Skills column
+    pub skills: Vec<String>,
+    /// Normalizations applied while reading the row, recorded for `--explain`
+    #[serde(skip)]
+    pub changes: Vec<String>,
+    /// Preferred company name shown in output (e.g. "Google" for "Alphabet Inc."), if the input has one
+    pub display_name: Option<String>,
+    /// Employment type such as "Full-time" or "Contract", if the input has one
+    pub employment_type: Option<String>,
+    /// Position of the entry among all input rows, in read order, used by
+    /// `--keep-order-stable` to break ties between equal sort keys
+    #[serde(skip)]
+    pub source_index: usize,
+    /// Name of the reference for the job: the Reference Name column, or the
+    /// supervisor when the input has none
+    pub reference_name: Option<String>,
+    /// Phone number or email address of the reference, if the input has one
+    pub reference_contact: Option<String>,
+    /// ZIP code at the end of the address, if it has one
+    pub postal_code: Option<String>,
+    /// Keywords from the optional Keywords column, without duplicates
+    pub keywords: Vec<String>,
+    /// Company website from the optional Website column, with a scheme
+    pub company_url: Option<String>,
+}
+
+impl WorkHistory {
+    /// Formats the employment period as a resume-ready range.
+    /// 
+    /// # Arguments
+    /// * `fmt` - How each date is rendered
+    /// 
+    /// # Returns
+    /// * `String` - "MM/YYYY – MM/YYYY", or "MM/YYYY – Present" (using the
+    ///   format's separator and present label) for an ongoing job, a single
+    ///   date when both ends render the same (e.g. "2020" with a year-only
+    ///   format), or an empty string for an undated entry
+    pub fn date_range_string(&self, fmt: &DateFormat) -> String {
+        let Some(start_date) = self.start_date else {
+            return String::new();
+        };
+        let start = fmt.format(start_date);
+        let end = match self.end_date {
+            Some(end_date) => fmt.format(end_date),
+            None => fmt.present_label.clone(),
+        };
+        if start == end {
+            return start;
+        }
+        format!("{}{}{}", start, fmt.separator, end)
+    }
+
+    /// The company name to show in output: the display name when there is
+    /// one, otherwise the company name as written.
+    pub fn display_company(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.company)
+    }
+
+    /// Whether the entry has no dates at all.
+    pub fn is_undated(&self) -> bool {
+        self.start_date.is_none()
+    }
+
+    /// Rewrites every text field of the entry (including each skill and
+    /// keyword, and the optional fields that are present) with `rewrite`.
+    /// The company website is left as it is.
+    /// 
+    /// # Arguments
+    /// * `rewrite` - Produces the new value of a field from its current value
+    pub fn rewrite_text(&mut self, rewrite: impl Fn(&str) -> String) {
+        for field in [&mut self.company, &mut self.position, &mut self.location, &mut self.responsibilities] {
+            *field = rewrite(field);
+        }
+        for tag in self.skills.iter_mut().chain(&mut self.keywords) {
+            *tag = rewrite(tag);
+        }
+        let optional = [
+            &mut self.department,
+            &mut self.salary,
+            &mut self.display_name,
+            &mut self.employment_type,
+            &mut self.reference_name,
+            &mut self.reference_contact,
+            &mut self.postal_code,
+        ];
+        for field in optional.into_iter().flatten() {
+            *field = rewrite(field);
+        }
+    }
+
+    /// The employment period as a half-open range of month numbers (see
+    /// [`month_number`]), with an ongoing job running until `today`.
+    /// 
+    /// # Arguments
+    /// * `today` - The date an ongoing job is measured to
+    /// 
+    /// # Returns
+    /// * `Option<(i32, i32)>` - The first month and the month after the last,
+    ///   so the difference is the tenure in months, or `None` for an undated
+    ///   entry
+    pub fn month_span(&self, today: NaiveDate) -> Option<(i32, i32)> {
+        let start = month_number(self.start_date?);
+        let end = self.end_date.unwrap_or(today);
+        Some((start, month_number(end).max(start)))
+    }
+
+    /// The tenure in whole months (see [`WorkHistory::month_span`]), with an
+    /// ongoing job measured until `today`, or `None` for an undated entry.
+    pub fn tenure_months(&self, today: NaiveDate) -> Option<i32> {
+        self.month_span(today).map(|(start, end)| end - start)
+    }
+}
+
+/// Numbers months consecutively (year * 12 + zero-based month), so that
+/// subtracting two month numbers gives the whole months between them.
+/// 
+/// # Arguments
+/// * `date` - Any date in the month
+/// 
+/// # Returns
+/// * `i32` - The month number
+pub fn month_number(date: NaiveDate) -> i32 {
+    date.year() * 12 + date.month0() as i32
+}
+
+/// How individual dates are rendered in output.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DateFormat {
+    /// chrono format string, e.g. "%m/%Y"
+    pub pattern: String,
+    /// Text written in place of the end date of an ongoing job
+    pub present_label: String,
+    /// Text written between the start and end of a date range
+    pub separator: String,
+}
+
+impl DateFormat {
+    /// Creates a date format from a chrono format string.
+    pub fn new(pattern: impl Into<String>) -> Self {
+        DateFormat {
+            pattern: pattern.into(),
+            present_label: PRESENT_LABEL.to_string(),
+            separator: RANGE_SEPARATOR.to_string(),
+        }
+    }
+
+    /// Replaces the label written for ongoing jobs.
+    pub fn with_present_label(mut self, label: impl Into<String>) -> Self {
+        self.present_label = label.into();
+        self
+    }
+
+    /// Replaces the text written between the ends of a date range.
+    pub fn with_separator(mut self, separator: impl Into<String>) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    /// Renders a date with this format.
+    pub fn format(&self, date: NaiveDate) -> String {
+        date.format(&self.pattern).to_string()
+    }
+}
+
+impl Default for DateFormat {
+    /// The MM/YYYY format used for date ranges.
+    fn default() -> Self {
+        DateFormat::new("%m/%Y")
+    }
+}
+
+/// Default label written in place of an end date for ongoing jobs.
+pub const PRESENT_LABEL: &str = "Present";
+
+/// Default text between the start and end of a date range.
+pub const RANGE_SEPARATOR: &str = " – ";
+
+/// Formats a NaiveDate into the MM/YYYY format.
+/// 
+/// # Arguments
+/// * `date` - A NaiveDate to format
+/// 
+/// # Returns
+/// * `String` - The date formatted as MM/01/YYYY
+pub fn format_date(date: NaiveDate) -> String {
+    date.format("%m/01/%Y").to_string()
+}
+
+/// Formats an end date, writing the present label for ongoing jobs.
+/// 
+/// # Arguments
+/// * `date` - The end date, or `None` for an ongoing job
+/// * `present_label` - Text written for an ongoing job, e.g. "Present"
+/// 
+/// # Returns
+/// * `String` - The formatted date or the present label
+pub fn format_end_date(date: Option<NaiveDate>, present_label: &str) -> String {
+    date.map(format_date).unwrap_or_else(|| present_label.to_string())
+}
+
+/// Describes a number of entries for messages, e.g. "12 entries" or "1 entry".
+pub fn entry_count(entries: usize) -> String {
+    if entries == 1 {
+        "1 entry".to_string()
+    } else {
+        format!("{} entries", entries)
+    }
+}
+
+/// Describes a number of files for messages, e.g. "3 files" or "1 file".
+pub fn file_count(files: usize) -> String {
+    if files == 1 {
+        "1 file".to_string()
+    } else {
+        format!("{} files", files)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::entry;
+
+    #[test]
+    fn date_range_string_formats_closed_range() {
+        let history = entry("Acme", "03/15/2020", "07/04/2021");
+        assert_eq!(history.date_range_string(&DateFormat::default()), "03/2020 – 07/2021");
+    }
+
+    #[test]
+    fn date_range_string_formats_present_range() {
+        let history = entry("Acme", "03/15/2020", "Present");
+        assert_eq!(history.date_range_string(&DateFormat::default()), "03/2020 – Present");
+    }
+
+    #[test]
+    fn date_range_string_uses_given_format() {
+        let history = entry("Acme", "03/15/2020", "07/04/2021");
+        assert_eq!(history.date_range_string(&DateFormat::new("%b %Y")), "Mar 2020 – Jul 2021");
+    }
+
+    #[test]
+    fn date_range_string_uses_custom_present_label() {
+        let history = entry("Acme", "03/15/2020", "Present");
+        let fmt = DateFormat::default().with_present_label("Current");
+        assert_eq!(history.date_range_string(&fmt), "03/2020 – Current");
+    }
+
+    #[test]
+    fn rewrite_text_covers_every_present_text_field() {
+        let mut history = entry("acme", "01/01/2020", "Present");
+        history.skills = vec!["rust".to_string()];
+        history.keywords = vec!["billing".to_string()];
+        history.department = Some("eng".to_string());
+
+        history.rewrite_text(|text| text.to_uppercase());
+        assert_eq!(history.company, "ACME");
+        assert_eq!(history.position, "ENGINEER");
+        assert_eq!(history.location, "SPRINGFIELD, IL");
+        assert_eq!(history.responsibilities, "BUILD THINGS");
+        assert_eq!(history.skills, ["RUST"]);
+        assert_eq!(history.keywords, ["BILLING"]);
+        assert_eq!(history.department.as_deref(), Some("ENG"));
+        assert_eq!(history.salary, None);
+    }
+
+    #[test]
+    fn date_range_string_with_year_only_format() {
+        let fmt = DateFormat::new("%Y");
+        assert_eq!(entry("Acme", "03/15/2020", "07/04/2022").date_range_string(&fmt), "2020 – 2022");
+        // A range within one year is a single year
+        assert_eq!(entry("Acme", "03/15/2020", "11/04/2020").date_range_string(&fmt), "2020");
+        assert_eq!(entry("Acme", "03/15/2020", "Present").date_range_string(&fmt), "2020 – Present");
+    }
+
+    #[test]
+    fn entry_count_uses_the_singular_for_one() {
+        assert_eq!(entry_count(0), "0 entries");
+        assert_eq!(entry_count(1), "1 entry");
+        assert_eq!(entry_count(2), "2 entries");
+        assert_eq!(file_count(1), "1 file");
+        assert_eq!(file_count(3), "3 files");
+    }
+}
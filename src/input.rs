@@ -0,0 +1,1524 @@
+//! Reading work history entries from CSV input.
+
+use anyhow::{anyhow, Context, Result};
+use calamine::{open_workbook, Data, DataType, Range, Reader, Xlsx};
+use chrono::{Datelike, NaiveDate};
+use csv::{ReaderBuilder, StringRecord};
+use indicatif::{ProgressBar, ProgressFinish, ProgressStyle};
+use std::collections::HashSet;
+use std::io::{self, BufRead, BufReader, Cursor, IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::columns::{ColumnMap, Field};
+use crate::error::InputError;
+use crate::history::{format_date, WorkHistory};
+use crate::options::{DateMode, Options};
+
+/// Date formats tried in order, e.g. "03/15/2021" and "March 15, 2021".
+const DATE_FORMATS: [&str; 2] = ["%m/%d/%Y", "%B %d, %Y"];
+
+/// The date formats with slashed dates read day first, e.g. "15/03/2021", for `--day-first`.
+const DAY_FIRST_DATE_FORMATS: [&str; 2] = ["%d/%m/%Y", "%B %d, %Y"];
+
+/// Date formats without a day, e.g. "January 2020", read as the 1st of the month.
+const MONTH_FORMATS: [&str; 1] = ["%B %Y"];
+
+/// Parses a date string into a NaiveDate.
+/// 
+/// MM/DD/YYYY is expected, but spelled-out months such as "March 15, 2021"
+/// or "January 2020" (the 1st of the month) are accepted too. Month names
+/// match regardless of case and may be abbreviated ("Jan 2020"), and runs
+/// of whitespace count as one space.
+/// 
+/// # Arguments
+/// * `date` - A string slice containing the date, usually in MM/DD/YYYY format
+/// 
+/// # Returns
+/// * `Result<NaiveDate>` - The parsed date or an error with context
+pub fn parse_date(date: &str) -> Result<NaiveDate> {
+    parse_date_with_formats(date, &DATE_FORMATS)
+}
+
+/// Parses a date as [`parse_date`] does, trying `formats` before the
+/// day-less month formats.
+fn parse_date_with_formats(date: &str, formats: &[&str]) -> Result<NaiveDate> {
+    let normalized = date.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    formats
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(&normalized, format).ok())
+        .or_else(|| {
+            // chrono needs a day, so supply the 1st
+            MONTH_FORMATS.iter().find_map(|format| {
+                NaiveDate::parse_from_str(&format!("1 {}", normalized), &format!("%d {}", format)).ok()
+            })
+        })
+        .with_context(|| format!("Failed to parse date: {}", date))
+}
+
+/// Parses a date string in the given `--date-mode`: like [`parse_date`]
+/// when lenient, the same with DD/MM/YYYY in place of MM/DD/YYYY when day
+/// first, and only as YYYY-MM-DD when ISO-strict.
+/// 
+/// # Arguments
+/// * `date` - A string slice containing the date
+/// * `mode` - Which date forms to accept
+/// 
+/// # Returns
+/// * `Result<NaiveDate>` - The parsed date or an error with context
+pub fn parse_date_in(date: &str, mode: DateMode) -> Result<NaiveDate> {
+    match mode {
+        DateMode::Lenient => parse_date(date),
+        DateMode::DayFirst => parse_date_with_formats(date, &DAY_FIRST_DATE_FORMATS),
+        DateMode::IsoStrict => Some(date.trim())
+            .filter(|iso| iso.len() == 10)
+            .and_then(|iso| NaiveDate::parse_from_str(iso, "%Y-%m-%d").ok())
+            .with_context(|| format!("Failed to parse date: {} (expected YYYY-MM-DD)", date)),
+    }
+}
+
+/// Rounds a date down to the first of its month, for `--round-dates-to-month`.
+/// 
+/// # Arguments
+/// * `date` - The parsed date
+/// 
+/// # Returns
+/// * `NaiveDate` - The same month, on day 1
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+/// Parses an end date, treating a blank value or "Present"/"Current" as ongoing.
+/// 
+/// # Arguments
+/// * `date` - A string slice containing the date in MM/DD/YYYY format, or an ongoing marker
+/// 
+/// # Returns
+/// * `Result<Option<NaiveDate>>` - The parsed date, `None` for an ongoing job, or an error with context
+pub fn parse_end_date(date: &str) -> Result<Option<NaiveDate>> {
+    parse_end_date_in(date, DateMode::Lenient)
+}
+
+/// Parses an end date in the given `--date-mode`, see [`parse_end_date`].
+/// 
+/// # Arguments
+/// * `date` - A string slice containing the date, or an ongoing marker
+/// * `mode` - Which date forms to accept
+/// 
+/// # Returns
+/// * `Result<Option<NaiveDate>>` - The parsed date, `None` for an ongoing job, or an error with context
+pub fn parse_end_date_in(date: &str, mode: DateMode) -> Result<Option<NaiveDate>> {
+    let date = date.trim();
+    if date.is_empty() || date.eq_ignore_ascii_case("present") || date.eq_ignore_ascii_case("current") {
+        return Ok(None);
+    }
+
+    parse_date_in(date, mode).map(Some)
+}
+
+/// US state (and District of Columbia) postal codes and names.
+const US_STATES: [(&str, &str); 51] = [
+    ("AL", "Alabama"), ("AK", "Alaska"), ("AZ", "Arizona"), ("AR", "Arkansas"),
+    ("CA", "California"), ("CO", "Colorado"), ("CT", "Connecticut"), ("DE", "Delaware"),
+    ("DC", "District of Columbia"), ("FL", "Florida"), ("GA", "Georgia"), ("HI", "Hawaii"),
+    ("ID", "Idaho"), ("IL", "Illinois"), ("IN", "Indiana"), ("IA", "Iowa"), ("KS", "Kansas"),
+    ("KY", "Kentucky"), ("LA", "Louisiana"), ("ME", "Maine"), ("MD", "Maryland"),
+    ("MA", "Massachusetts"), ("MI", "Michigan"), ("MN", "Minnesota"), ("MS", "Mississippi"),
+    ("MO", "Missouri"), ("MT", "Montana"), ("NE", "Nebraska"), ("NV", "Nevada"),
+    ("NH", "New Hampshire"), ("NJ", "New Jersey"), ("NM", "New Mexico"), ("NY", "New York"),
+    ("NC", "North Carolina"), ("ND", "North Dakota"), ("OH", "Ohio"), ("OK", "Oklahoma"),
+    ("OR", "Oregon"), ("PA", "Pennsylvania"), ("RI", "Rhode Island"), ("SC", "South Carolina"),
+    ("SD", "South Dakota"), ("TN", "Tennessee"), ("TX", "Texas"), ("UT", "Utah"), ("VT", "Vermont"),
+    ("VA", "Virginia"), ("WA", "Washington"), ("WV", "West Virginia"), ("WI", "Wisconsin"),
+    ("WY", "Wyoming"),
+];
+
+/// Suffixes of address parts that name a county rather than a city.
+const COUNTY_SUFFIXES: [&str; 3] = [" county", " parish", " borough"];
+
+/// Extracts city and state from an address string.
+/// 
+/// The address is expected to be the already-parsed CSV field, so any quoting
+/// has been removed and every comma left in it is part of the address itself.
+/// The last two comma-separated parts are taken as city and state, with a
+/// trailing ZIP code dropped from the state. When the last part is a known US
+/// state (a two-letter code or a full name such as "New York"), a county part
+/// before it ("Kings County") is skipped to find the city, and a state written
+/// after the city without a comma ("Springfield IL") is split off.
+/// 
+/// # Arguments
+/// * `address` - A string slice containing the full address
+/// 
+/// # Returns
+/// * `String` - Formatted "City, State" or the trimmed original string if parsing fails
+pub fn extract_location(address: &str) -> String {
+    let parts: Vec<&str> = address
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect();
+
+    let Some((&last, rest)) = parts.split_last() else {
+        return String::new();
+    };
+    let state = strip_zip_code(last);
+
+    // "Springfield IL 62701": the city and state share the last part
+    if !is_us_state(state) {
+        if let Some((city, state)) = split_trailing_state(state) {
+            return format!("{}, {}", city, state);
+        }
+    }
+
+    let city = if is_us_state(state) {
+        rest.iter().rev().find(|part| !is_county(part)).or(rest.last())
+    } else {
+        rest.last()
+    };
+    match city {
+        None => last.to_string(),
+        Some(city) if state.is_empty() => city.to_string(),
+        Some(city) => format!("{}, {}", city, state),
+    }
+}
+
+/// Extracts the ZIP code at the end of an address, e.g. "62701" or
+/// "62701-1234". A 9-digit ZIP written without the dash is returned in
+/// ZIP+4 form.
+/// 
+/// # Arguments
+/// * `address` - A string slice containing the full address
+/// 
+/// # Returns
+/// * `Option<String>` - The ZIP code, or `None` if the address doesn't end with one
+pub fn extract_zip_code(address: &str) -> Option<String> {
+    let last = address.trim().trim_end_matches(',').split([',', ' ']).next_back()?;
+    let digits = |part: &str, count: usize| part.len() == count && part.chars().all(|c| c.is_ascii_digit());
+
+    match last.split_once('-') {
+        Some((zip, plus_four)) if digits(zip, 5) && digits(plus_four, 4) => Some(last.to_string()),
+        None if digits(last, 5) => Some(last.to_string()),
+        None if digits(last, 9) => Some(format!("{}-{}", &last[..5], &last[5..])),
+        _ => None,
+    }
+}
+
+/// Whether a value is a US state's two-letter code or full name, ignoring case.
+fn is_us_state(value: &str) -> bool {
+    US_STATES
+        .iter()
+        .any(|(code, name)| value.eq_ignore_ascii_case(code) || value.eq_ignore_ascii_case(name))
+}
+
+/// Whether an address part names a county, e.g. "Kings County".
+fn is_county(part: &str) -> bool {
+    let part = part.to_lowercase();
+    part == "county" || COUNTY_SUFFIXES.iter().any(|suffix| part.ends_with(suffix))
+}
+
+/// Splits a known US state off the end of an address part, trying the
+/// longest state first so "West Virginia" isn't read as "Virginia".
+/// 
+/// # Arguments
+/// * `part` - An address part such as "Springfield IL" or "Albany New York"
+/// 
+/// # Returns
+/// * `Option<(&str, &str)>` - The city and the state, if the part ends with a state after a city
+fn split_trailing_state(part: &str) -> Option<(&str, &str)> {
+    // Each space is a possible boundary between the city and the state
+    part.match_indices(' ').find_map(|(index, _)| {
+        let (city, state) = (part[..index].trim_end(), part[index..].trim());
+        (!city.is_empty() && is_us_state(state)).then_some((city, state))
+    })
+}
+
+/// Removes a trailing ZIP code (e.g. "62701" or "62701-1234") from a state part.
+/// 
+/// # Arguments
+/// * `state_part` - The last comma-separated part of an address
+/// 
+/// # Returns
+/// * `&str` - The state part without any trailing ZIP code
+fn strip_zip_code(state_part: &str) -> &str {
+    match state_part.rsplit_once(char::is_whitespace) {
+        Some((state, zip)) if zip.chars().all(|c| c.is_ascii_digit() || c == '-') => state.trim_end(),
+        _ => state_part,
+    }
+}
+
+/// Converts a CSV read error into a descriptive error.
+/// 
+/// Column count mismatches (only reported when the reader is not flexible)
+/// and invalid UTF-8 name the offending row; other errors keep the csv
+/// crate's message.
+/// 
+/// # Arguments
+/// * `err` - The error returned by the csv reader
+/// 
+/// # Returns
+/// * `anyhow::Error` - The error with context
+fn describe_csv_error(err: csv::Error) -> anyhow::Error {
+    if let csv::ErrorKind::UnequalLengths { pos, expected_len, len } = err.kind() {
+        return InputError::ColumnCount {
+            row: pos.as_ref().map(|pos| pos.line()).unwrap_or_default(),
+            found: *len,
+            expected: *expected_len,
+        }
+        .into();
+    }
+    if let csv::ErrorKind::Utf8 { pos, err } = err.kind() {
+        return InputError::InvalidUtf8 {
+            row: pos.as_ref().map(|pos| pos.line()).unwrap_or_default(),
+            byte: pos.as_ref().map(|pos| pos.byte()).unwrap_or_default(),
+            column: err.field() + 1,
+        }
+        .into();
+    }
+
+    anyhow::Error::new(err).context("Failed to read CSV record")
+}
+
+/// Joins the non-empty values of the given columns with "; ".
+/// 
+/// Columns that are missing from the record or blank are skipped.
+/// 
+/// # Arguments
+/// * `record` - The CSV record to read from
+/// * `columns` - Column indices to join, in order
+/// 
+/// # Returns
+/// * `String` - The joined text
+fn join_columns(record: &csv::StringRecord, columns: &[usize]) -> String {
+    columns
+        .iter()
+        .filter_map(|&index| record.get(index))
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Converts an optional text value to `None` when it is blank.
+/// 
+/// # Arguments
+/// * `value` - The raw cell value
+/// 
+/// # Returns
+/// * `Option<String>` - The trimmed value, or `None` if it is empty
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Loosely checks that a value is shaped like an email address: one `@`
+/// with text before it, a domain containing a dot after it, and no spaces.
+/// 
+/// # Arguments
+/// * `value` - The value to check
+/// 
+/// # Returns
+/// * `bool` - Whether the value looks like an email address
+pub fn looks_like_email(value: &str) -> bool {
+    let Some((local, domain)) = value.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !domain.contains('@')
+        && !value.contains(char::is_whitespace)
+        && domain.split('.').count() > 1
+        && domain.split('.').all(|part| !part.is_empty())
+}
+
+/// Collapses runs of spaces and tabs to single spaces and trims each line,
+/// for `--collapse-whitespace`. Line breaks are kept, so multiline cells
+/// still split into bullets, but blank lines are dropped.
+/// 
+/// # Arguments
+/// * `text` - The field value
+/// 
+/// # Returns
+/// * `String` - The value with its whitespace collapsed
+pub fn collapse_ws(text: &str) -> String {
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Removes the marker Excel exports put before a cell to keep it text: a
+/// leading apostrophe, as in "'00123", and with `strip_equals` (for
+/// `--strip-formula-prefix`) a leading "=" too, unquoting `="..."`.
+/// 
+/// # Arguments
+/// * `value` - The raw cell value
+/// * `strip_equals` - Whether to remove a leading "=" as well
+/// 
+/// # Returns
+/// * `&str` - The value without its guard
+pub fn strip_excel_guard(value: &str, strip_equals: bool) -> &str {
+    let value = value.strip_prefix('\'').unwrap_or(value);
+    if !strip_equals {
+        return value;
+    }
+    match value.strip_prefix('=') {
+        Some(formula) => formula
+            .strip_prefix('"')
+            .and_then(|quoted| quoted.strip_suffix('"'))
+            .unwrap_or(formula),
+        None => value,
+    }
+}
+
+/// Adds "https://" to a URL written without a scheme, e.g. "acme.com".
+/// 
+/// # Arguments
+/// * `url` - The trimmed cell value
+/// 
+/// # Returns
+/// * `String` - The URL with a scheme
+fn with_url_scheme(url: &str) -> String {
+    let has_scheme = url.split_once("://").is_some_and(|(scheme, _)| {
+        !scheme.is_empty() && scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+    });
+    if has_scheme {
+        url.to_string()
+    } else {
+        format!("https://{}", url)
+    }
+}
+
+/// Normalizes common spellings and abbreviations of an employment type, e.g.
+/// "FT" to "Full-time". Unrecognized values are kept as written.
+/// 
+/// # Arguments
+/// * `value` - The trimmed cell value
+/// 
+/// # Returns
+/// * `String` - The normalized employment type
+fn normalize_employment_type(value: &str) -> String {
+    let key: String = value
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect();
+    let normalized = match key.as_str() {
+        "ft" | "fulltime" => "Full-time",
+        "pt" | "parttime" => "Part-time",
+        "contract" | "contractor" | "c2c" | "1099" => "Contract",
+        "temp" | "temporary" => "Temporary",
+        "intern" | "internship" => "Internship",
+        "freelance" | "freelancer" => "Freelance",
+        _ => return value.to_string(),
+    };
+    normalized.to_string()
+}
+
+/// Splits a comma-separated list of tags, dropping blanks.
+/// 
+/// # Arguments
+/// * `value` - The raw cell value, e.g. "Rust, Python"
+/// 
+/// # Returns
+/// * `Vec<String>` - The trimmed tags in order
+fn split_tags(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Drops repeated tags, ignoring case and keeping the first spelling of each.
+/// 
+/// # Arguments
+/// * `tags` - The tags in order
+/// 
+/// # Returns
+/// * `Vec<String>` - The tags in order, each only once
+fn dedup_tags(tags: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    tags.into_iter().filter(|tag| seen.insert(tag.to_lowercase())).collect()
+}
+
+/// Checks whether a record repeats the header row, ignoring surrounding whitespace.
+/// 
+/// # Arguments
+/// * `record` - The record to check
+/// * `headers` - The header row
+/// 
+/// # Returns
+/// * `bool` - True if every field matches the header in the same position
+fn is_header_row(record: &csv::StringRecord, headers: &csv::StringRecord) -> bool {
+    record.len() == headers.len() && record.iter().zip(headers).all(|(field, header)| field.trim() == header.trim())
+}
+
+/// Describes how a row's raw values were normalized into its entry.
+/// 
+/// # Arguments
+/// * `field` - Looks up the raw value of a field in the row
+/// * `history` - The entry read from the row
+/// * `swapped` - Whether reversed start and end dates were swapped
+/// * `responsibility_columns` - Columns the responsibilities were joined from
+/// 
+/// # Returns
+/// * `Vec<String>` - One short description per change, in field order
+fn explain_row<'a>(
+    field: impl Fn(Field) -> &'a str,
+    history: &WorkHistory,
+    swapped: bool,
+    responsibility_columns: &[usize],
+) -> Vec<String> {
+    let mut changes = Vec::new();
+    let raw_start = field(Field::StartDate).trim();
+    let raw_end = field(Field::EndDate).trim();
+
+    match history.start_date {
+        None => changes.push("no start or end date; read as undated".to_string()),
+        Some(start_date) => {
+            if swapped {
+                changes.push(format!("start date {} and end date {} swapped", raw_start, raw_end));
+            }
+            let (raw_start, raw_end) = if swapped { (raw_end, raw_start) } else { (raw_start, raw_end) };
+            if format_date(start_date) != raw_start {
+                changes.push(format!("start date {} written as {}", raw_start, format_date(start_date)));
+            }
+            match history.end_date {
+                Some(end_date) if format_date(end_date) != raw_end => {
+                    changes.push(format!("end date {} written as {}", raw_end, format_date(end_date)));
+                }
+                Some(_) => {}
+                None if raw_end.is_empty() => changes.push("empty end date read as ongoing".to_string()),
+                None => changes.push(format!("end date \"{}\" read as ongoing", raw_end)),
+            }
+        }
+    }
+
+    let raw_address = field(Field::Address).trim();
+    if history.location != raw_address {
+        changes.push(format!("address \"{}\" reduced to \"{}\"", raw_address, history.location));
+    }
+    if responsibility_columns.len() > 1 {
+        let columns: Vec<String> = responsibility_columns.iter().map(usize::to_string).collect();
+        changes.push(format!("responsibilities joined from columns {}", columns.join(", ")));
+    }
+    for (name, raw, value) in [
+        ("department", field(Field::Department), &history.department),
+        ("salary", field(Field::Salary), &history.salary),
+    ] {
+        if value.as_deref().is_some_and(|value| value != raw) {
+            changes.push(format!("{} trimmed", name));
+        }
+    }
+
+    changes
+}
+
+/// Prefix of the comment line that declares the column order.
+const COLS_DIRECTIVE: &str = "#cols:";
+
+/// Reads a leading `#cols:` directive, e.g. `#cols: company,title,start,end`.
+/// 
+/// # Arguments
+/// * `reader` - The input, positioned at its first line
+/// 
+/// # Returns
+/// * `Result<(Option<StringRecord>, Vec<u8>)>` - The declared column names
+///   and an empty line standing in for the directive (so row numbers still
+///   match the file), or `None` and the first line, which must still be parsed
+fn read_cols_directive<R: BufRead>(reader: &mut R) -> Result<(Option<StringRecord>, Vec<u8>)> {
+    let mut first_line = Vec::new();
+    reader.read_until(b'\n', &mut first_line).context("Failed to read the first line")?;
+
+    let line = String::from_utf8_lossy(&first_line);
+    match line.trim_start_matches('\u{feff}').strip_prefix(COLS_DIRECTIVE) {
+        Some(names) => {
+            let names: Vec<&str> = names.trim().split(',').map(str::trim).collect();
+            Ok((Some(StringRecord::from(names)), b"\n".to_vec()))
+        }
+        None => Ok((None, first_line)),
+    }
+}
+
+/// Reads work history entries from CSV data.
+/// 
+/// Columns are located by header name (see [`ColumnMap`]), falling back to
+/// the original positional layout. With `cols_directive`, a leading
+/// `#cols: company,title,...` line takes the place of the header row.
+/// 
+/// # Arguments
+/// * `reader` - Source of CSV data, including the header row
+/// * `options` - Processing options; `strict` rejects rows whose column count
+///   differs from the header, `fix_dates` swaps reversed start/end dates, and
+///   `first_line_headers_only` skips later rows that repeat the header
+/// * `warnings` - Collects warnings about rows that were repaired
+/// 
+/// # Returns
+/// * `Result<Vec<WorkHistory>>` - The parsed entries in file order
+pub fn read_work_histories<R: Read>(
+    reader: R,
+    options: &Options,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<WorkHistory>> {
+    let mut reader = BufReader::new(reader);
+    let (directive, first_line) = if options.cols_directive {
+        read_cols_directive(&mut reader)?
+    } else {
+        (None, Vec::new())
+    };
+
+    let mut builder = ReaderBuilder::new();
+    builder.has_headers(directive.is_none()).flexible(!options.strict);
+    if let Some(terminator) = options.terminator {
+        builder.terminator(terminator);
+    }
+    let mut rdr = builder.from_reader(Cursor::new(first_line).chain(reader));
+
+    let headers = match directive {
+        Some(directive) => directive,
+        None => rdr.headers().map_err(describe_csv_error)?.clone(),
+    };
+    let columns = ColumnMap::from_headers(&headers)?;
+    columns.require(&options.required_columns)?;
+    let description_column: Vec<usize> = columns.get(Field::Description).into_iter().collect();
+    let responsibility_columns = if options.responsibility_columns.is_empty() {
+        &description_column
+    } else {
+        &options.responsibility_columns
+    };
+
+    let null_dates = options.null_dates();
+    let mut work_histories = Vec::new();
+    let mut repeated_headers = 0;
+    // The spinner only appears once the input turns out to be large
+    let show_progress = !options.quiet && io::stderr().is_terminal();
+    let mut progress: Option<ProgressBar> = None;
+
+    // Parse CSV records
+    for result in rdr.records() {
+        let mut record = result.map_err(describe_csv_error)?;
+        if record.iter().any(|value| value.starts_with(['\'', '='])) {
+            let mut unguarded: StringRecord =
+                record.iter().map(|value| strip_excel_guard(value, options.strip_formula_prefix)).collect();
+            unguarded.set_position(record.position().cloned());
+            record = unguarded;
+        }
+
+        // Skip header rows left over from concatenating several files
+        if options.first_line_headers_only && is_header_row(&record, &headers) {
+            repeated_headers += 1;
+            continue;
+        }
+
+        let row = record.position().map(|pos| pos.line()).unwrap_or_default();
+        let date_error = |value: &str| match options.date_mode {
+            DateMode::IsoStrict => InputError::NonIsoDate { row, value: value.to_string() },
+            _ => InputError::DateParse { row, value: value.to_string() },
+        };
+        let field = |field: Field| columns.value(&record, field).unwrap_or("");
+        
+        // Entries with no dates at all (e.g. volunteer work) are undated
+        let is_null = |value: &str| {
+            let value = value.trim();
+            value.is_empty() || null_dates.iter().any(|null| value.eq_ignore_ascii_case(null))
+        };
+        let undated = is_null(field(Field::StartDate)) && is_null(field(Field::EndDate));
+        let (mut start, mut end_date) = if undated {
+            (None, None)
+        } else {
+            let start_date = parse_date_in(field(Field::StartDate), options.date_mode).map_err(|_| date_error(field(Field::StartDate)))?;
+            let end_date = if is_null(field(Field::EndDate)) {
+                None
+            } else {
+                parse_end_date_in(field(Field::EndDate), options.date_mode).map_err(|_| date_error(field(Field::EndDate)))?
+            };
+            (Some(start_date), end_date)
+        };
+        if options.round_dates_to_month {
+            start = start.map(first_of_month);
+            end_date = end_date.map(first_of_month);
+        }
+
+        // Reject (or with --fix-dates, swap) a start date after the end date
+        let mut swapped = false;
+        if let Some((start_date, end)) = start.zip(end_date).filter(|(start_date, end)| start_date > end) {
+            if !options.fix_dates {
+                return Err(InputError::DateOrder {
+                    row,
+                    start: field(Field::StartDate).to_string(),
+                    end: field(Field::EndDate).to_string(),
+                }
+                .into());
+            }
+            warnings.push(format!(
+                "Row {}: start date {} is after end date {}; swapped them",
+                row,
+                field(Field::StartDate),
+                field(Field::EndDate)
+            ));
+            end_date = Some(start_date);
+            start = Some(end);
+            swapped = true;
+        }
+        
+        let mut work_history = WorkHistory {
+            company: field(Field::Company).to_string(),
+            position: field(Field::Position).to_string(),
+            start_date: start,
+            end_date,
+            department: non_empty(field(Field::Department)),
+            location: extract_location(field(Field::Address)),
+            postal_code: extract_zip_code(field(Field::Address)),
+            responsibilities: join_columns(&record, responsibility_columns),
+            salary: non_empty(field(Field::Salary)),
+            skills: split_tags(field(Field::Skills)),
+            changes: Vec::new(),
+            display_name: non_empty(field(Field::DisplayName)),
+            employment_type: non_empty(field(Field::EmploymentType)).map(|value| normalize_employment_type(&value)),
+            source_index: work_histories.len(),
+            reference_name: non_empty(field(Field::ReferenceName)).or_else(|| non_empty(field(Field::Supervisor))),
+            reference_contact: non_empty(field(Field::ReferenceContact)),
+            keywords: dedup_tags(split_tags(field(Field::Keywords))),
+            company_url: non_empty(field(Field::Website)).map(|url| with_url_scheme(&url)),
+        };
+        // Check the emails in the contact columns (only the reference contact without --validate-emails)
+        let contact_columns: &[Field] = if options.validate_emails {
+            &[Field::Supervisor, Field::ReferenceName, Field::ReferenceContact]
+        } else {
+            &[Field::ReferenceContact]
+        };
+        for &column in contact_columns {
+            let value = field(column).trim();
+            if !value.contains('@') || looks_like_email(value) {
+                continue;
+            }
+            if options.validate_emails && options.strict {
+                return Err(InputError::InvalidEmail {
+                    row,
+                    column: column.name().to_string(),
+                    value: value.to_string(),
+                }
+                .into());
+            }
+            warnings.push(format!(
+                "Row {}: {} \"{}\" doesn't look like an email address",
+                row,
+                column.name().to_lowercase(),
+                value
+            ));
+        }
+        if options.collapse_whitespace {
+            work_history.rewrite_text(collapse_ws);
+        }
+        if options.explain {
+            work_history.changes = explain_row(field, &work_history, swapped, responsibility_columns);
+        }
+        
+        work_histories.push(work_history);
+        if show_progress && work_histories.len() % PROGRESS_INTERVAL == 0 {
+            progress.get_or_insert_with(row_spinner).set_position(work_histories.len() as u64);
+        }
+    }
+
+    if repeated_headers == 1 {
+        warnings.push("Dropped 1 repeated header row".to_string());
+    } else if repeated_headers > 1 {
+        warnings.push(format!("Dropped {} repeated header rows", repeated_headers));
+    }
+
+    Ok(work_histories)
+}
+
+/// Number of rows between progress updates, and before the spinner appears.
+const PROGRESS_INTERVAL: usize = 1000;
+
+/// Creates the stderr spinner showing how many rows have been parsed.
+/// 
+/// The row count isn't known until the input is read, so this is a spinner
+/// with a running count rather than a bar; it is cleared when dropped.
+/// 
+/// # Returns
+/// * `ProgressBar` - The running spinner
+fn row_spinner() -> ProgressBar {
+    let spinner = ProgressBar::new_spinner()
+        .with_style(ProgressStyle::with_template("{spinner} Parsed {pos} rows").unwrap_or_else(|_| ProgressStyle::default_spinner()))
+        .with_finish(ProgressFinish::AndClear);
+    spinner.enable_steady_tick(Duration::from_millis(100));
+    spinner
+}
+
+/// Returns the input path as a URL if it is one to fetch over HTTP(S).
+/// 
+/// # Arguments
+/// * `path` - The input path as given on the command line
+/// 
+/// # Returns
+/// * `Option<&str>` - The URL, if the path starts with `http://` or `https://`
+pub fn input_url(path: &Path) -> Option<&str> {
+    path.to_str().filter(|path| {
+        let lowercase = path.to_ascii_lowercase();
+        lowercase.starts_with("http://") || lowercase.starts_with("https://")
+    })
+}
+
+/// Downloads an input file into memory.
+/// 
+/// # Arguments
+/// * `url` - The `http://` or `https://` URL of the CSV
+/// 
+/// # Returns
+/// * `Result<Vec<u8>>` - The body of the response
+/// 
+/// # Errors
+/// Returns an error if the request fails or the server doesn't answer with a success status
+pub fn fetch_input(url: &str) -> Result<Vec<u8>> {
+    let response = reqwest::blocking::get(url).with_context(|| format!("Failed to fetch {}", url))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(anyhow!("Failed to fetch {}: the server answered {}", url, status));
+    }
+
+    let body = response.bytes().with_context(|| format!("Failed to download {}", url))?;
+    Ok(body.to_vec())
+}
+
+/// Whether an input path is an Excel workbook, by its `.xlsx` extension.
+pub fn is_xlsx(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("xlsx"))
+}
+
+/// Reads a sheet of an Excel workbook as CSV, so that it goes through the
+/// same parsing as a CSV input.
+/// 
+/// # Arguments
+/// * `path` - The `.xlsx` file
+/// * `sheet` - Name of the sheet to read, or `None` for the first sheet
+/// * `mode` - The `--date-mode`, which decides how date cells are written
+/// 
+/// # Returns
+/// * `Result<Vec<u8>>` - The sheet's rows as CSV, header first
+/// 
+/// # Errors
+/// Returns an error if the workbook can't be opened or has no such sheet
+pub fn read_xlsx(path: &Path, sheet: Option<&str>, mode: DateMode) -> Result<Vec<u8>> {
+    let mut workbook: Xlsx<_> =
+        open_workbook(path).with_context(|| format!("Failed to open workbook: {}", path.display()))?;
+    let name = match sheet {
+        Some(name) => name.to_string(),
+        None => workbook
+            .sheet_names()
+            .first()
+            .cloned()
+            .ok_or_else(|| anyhow!("The workbook has no sheets: {}", path.display()))?,
+    };
+    let range = workbook
+        .worksheet_range(&name)
+        .with_context(|| format!("Failed to read sheet \"{}\" of {}", name, path.display()))?;
+
+    sheet_to_csv(&range, mode)
+}
+
+/// Writes the used cells of a sheet as CSV, one record per row.
+/// 
+/// # Arguments
+/// * `range` - The cells of the sheet
+/// * `mode` - The `--date-mode`, which decides how date cells are written
+/// 
+/// # Returns
+/// * `Result<Vec<u8>>` - The rows as CSV
+fn sheet_to_csv(range: &Range<Data>, mode: DateMode) -> Result<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for row in range.rows() {
+        writer.write_record(row.iter().map(|cell| cell_text(cell, mode)))?;
+    }
+    writer.into_inner().map_err(|err| anyhow!("Failed to convert the sheet to CSV: {}", err))
+}
+
+/// Text of a spreadsheet cell as it would appear in an exported CSV; date
+/// cells are written as MM/DD/YYYY, DD/MM/YYYY when day first, or
+/// YYYY-MM-DD when ISO-strict.
+fn cell_text(cell: &Data, mode: DateMode) -> String {
+    let format = match mode {
+        DateMode::Lenient => "%m/%d/%Y",
+        DateMode::DayFirst => "%d/%m/%Y",
+        DateMode::IsoStrict => "%Y-%m-%d",
+    };
+    match cell {
+        Data::DateTime(_) | Data::DateTimeIso(_) => match cell.as_datetime() {
+            Some(datetime) => datetime.format(format).to_string(),
+            None => cell.to_string(),
+        },
+        _ => cell.to_string(),
+    }
+}
+
+/// Expands a glob pattern into the input files it matches.
+/// 
+/// # Arguments
+/// * `pattern` - A glob pattern such as "history_*.csv"
+/// 
+/// # Returns
+/// * `Result<Vec<PathBuf>>` - The matching files, sorted by path
+/// 
+/// # Errors
+/// Returns an error if the pattern is invalid or matches no files
+pub fn expand_input_glob(pattern: &str) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for entry in glob::glob(pattern).with_context(|| format!("Invalid input glob: {}", pattern))? {
+        let path = entry.with_context(|| format!("Failed to read a match for input glob: {}", pattern))?;
+        if path.is_file() {
+            paths.push(path);
+        }
+    }
+
+    if paths.is_empty() {
+        return Err(anyhow!("No input files match: {}", pattern));
+    }
+
+    paths.sort();
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::DEFAULT_NULL_DATES;
+    use calamine::{ExcelDateTime, ExcelDateTimeType};
+    use crate::output::write_text;
+    use crate::test_support::HEADER;
+
+    /// Parses a single CSV data row (with the standard header) and returns its location.
+    fn location_from_row(row: &str) -> String {
+        let data = format!("{}{}\n", HEADER, row);
+        let histories = read_work_histories(data.as_bytes(), &Options::default(), &mut Vec::new()).unwrap();
+        histories[0].location.clone()
+    }
+
+    #[test]
+    fn extract_location_keeps_city_state() {
+        assert_eq!(extract_location("Springfield, IL"), "Springfield, IL");
+        assert_eq!(extract_location("  Springfield ,  IL "), "Springfield, IL");
+    }
+
+    #[test]
+    fn extract_location_reduces_street_address() {
+        assert_eq!(extract_location("123 Main St, Springfield, IL 62701"), "Springfield, IL");
+        assert_eq!(extract_location("Suite 4, 123 Main St, Springfield, IL 62701-1234"), "Springfield, IL");
+    }
+
+    #[test]
+    fn extract_location_strips_zip_from_city_state() {
+        assert_eq!(extract_location("Springfield, IL 62701"), "Springfield, IL");
+    }
+
+    #[test]
+    fn extract_location_handles_single_part_and_empty() {
+        assert_eq!(extract_location("Remote"), "Remote");
+        assert_eq!(extract_location(""), "");
+        assert_eq!(extract_location("Springfield, IL,"), "Springfield, IL");
+    }
+
+    #[test]
+    fn extract_location_skips_counties_before_a_state() {
+        assert_eq!(extract_location("City, County, New York 10001"), "City, New York");
+        assert_eq!(extract_location("12 Court St, Brooklyn, Kings County, NY 11201"), "Brooklyn, NY");
+        assert_eq!(extract_location("Baton Rouge, East Baton Rouge Parish, Louisiana"), "Baton Rouge, Louisiana");
+        assert_eq!(extract_location("Kings County, NY"), "Kings County, NY");
+    }
+
+    #[test]
+    fn extract_location_splits_a_state_written_after_the_city() {
+        assert_eq!(extract_location("123 Main St, Springfield IL 62701"), "Springfield, IL");
+        assert_eq!(extract_location("1 State St, Albany New York 12207"), "Albany, New York");
+        assert_eq!(extract_location("Charleston West Virginia"), "Charleston, West Virginia");
+        assert_eq!(extract_location("Kansas City MO"), "Kansas City, MO");
+        assert_eq!(extract_location("10 Downing St, London SW1A"), "10 Downing St, London SW1A");
+    }
+
+    #[test]
+    fn quoted_city_state_is_parsed_from_field() {
+        let row = r#"Acme,Engineer,01/01/2020,01/01/2021,"Springfield, IL",Jane,"Build things",Growth"#;
+        assert_eq!(location_from_row(row), "Springfield, IL");
+    }
+
+    #[test]
+    fn quoted_multi_comma_address_is_parsed_from_field() {
+        let row = r#"Acme,Engineer,01/01/2020,01/01/2021,"123 Main St, Springfield, IL 62701",Jane,"Build, test, ship",Growth"#;
+        assert_eq!(location_from_row(row), "Springfield, IL");
+
+        let row = r#"Acme,Engineer,01/01/2020,01/01/2021,"Suite 4, 123 Main St, Springfield, IL 62701",Jane,Build,Growth"#;
+        assert_eq!(location_from_row(row), "Springfield, IL");
+    }
+
+    #[test]
+    fn strict_mode_rejects_over_length_row() {
+        let data = format!(
+            "{}{}\n{}\n",
+            HEADER,
+            "Acme,Engineer,01/01/2020,01/01/2021,\"Springfield, IL\",Jane,Build,Growth",
+            "Acme,Engineer,01/01/2021,01/01/2022,\"Springfield, IL\",Jane,Build,Growth,Extra"
+        );
+        let options = Options { strict: true, ..Options::default() };
+
+        let err = read_work_histories(data.as_bytes(), &options, &mut Vec::new()).unwrap_err();
+        assert_eq!(err.to_string(), "Row 3 has 9 columns but the header has 8");
+
+        // Without --strict the extra trailing column is tolerated
+        assert_eq!(read_work_histories(data.as_bytes(), &Options::default(), &mut Vec::new()).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn strict_mode_rejects_under_length_row() {
+        let data = format!("{}{}\n", HEADER, "Acme,Engineer,01/01/2020,01/01/2021,\"Springfield, IL\",Jane,Build");
+        let options = Options { strict: true, ..Options::default() };
+
+        let err = read_work_histories(data.as_bytes(), &options, &mut Vec::new()).unwrap_err();
+        assert_eq!(err.to_string(), "Row 2 has 7 columns but the header has 8");
+    }
+
+    #[test]
+    fn join_responsibilities_skips_blank_and_missing_columns() {
+        let data = format!(
+            "{}{}\n",
+            HEADER,
+            "Acme,Engineer,01/01/2020,01/01/2021,\"Springfield, IL\",Jane,Build things,,Ship things"
+        );
+        let options = Options {
+            responsibility_columns: vec![6, 7, 8, 12],
+            ..Options::default()
+        };
+
+        let histories = read_work_histories(data.as_bytes(), &options, &mut Vec::new()).unwrap();
+        assert_eq!(histories[0].responsibilities, "Build things; Ship things");
+    }
+
+    #[test]
+    fn parse_end_date_accepts_ongoing_markers() {
+        assert_eq!(parse_end_date("").unwrap(), None);
+        assert_eq!(parse_end_date(" Present ").unwrap(), None);
+        assert_eq!(parse_end_date("current").unwrap(), None);
+        assert_eq!(parse_end_date("01/02/2020").unwrap(), NaiveDate::from_ymd_opt(2020, 1, 2));
+        assert!(parse_end_date("soon").is_err());
+    }
+
+    #[test]
+    fn parse_date_accepts_spelled_out_months() {
+        assert_eq!(parse_date("January 2020").unwrap(), NaiveDate::from_ymd_opt(2020, 1, 1).unwrap());
+        assert_eq!(parse_date("March 15, 2021").unwrap(), NaiveDate::from_ymd_opt(2021, 3, 15).unwrap());
+        assert_eq!(parse_date("MARCH  15,  2021").unwrap(), NaiveDate::from_ymd_opt(2021, 3, 15).unwrap());
+        assert_eq!(parse_date("sep 2019").unwrap(), NaiveDate::from_ymd_opt(2019, 9, 1).unwrap());
+        assert!(parse_date("Smarch 2020").is_err());
+        assert!(parse_date("February 30, 2021").is_err());
+    }
+
+    #[test]
+    fn date_errors_report_row_and_value() {
+        let data = format!(
+            "{}{}\n{}\n",
+            HEADER,
+            "Acme,Engineer,01/01/2020,01/01/2021,\"Springfield, IL\",Jane,Build,Growth",
+            "Acme,Engineer,13/40/2020,01/01/2022,\"Springfield, IL\",Jane,Build,Growth"
+        );
+
+        let err = read_work_histories(data.as_bytes(), &Options::default(), &mut Vec::new()).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<InputError>(),
+            Some(&InputError::DateParse { row: 3, value: "13/40/2020".to_string() })
+        );
+    }
+
+    #[test]
+    fn department_column_is_written_when_present() {
+        let data = "Company,Job Title,Department,Start Date,End Date,Address,Supervisor Name,Description\n\
+            Acme,Engineer,Platform,01/01/2020,01/01/2021,\"Springfield, IL\",Jane,Build things\n\
+            Other,Engineer,,01/01/2019,01/01/2020,\"Springfield, IL\",Jane,Build things\n";
+        let histories = read_work_histories(data.as_bytes(), &Options::default(), &mut Vec::new()).unwrap();
+        assert_eq!(histories[0].department.as_deref(), Some("Platform"));
+        assert_eq!(histories[0].responsibilities, "Build things");
+        assert_eq!(histories[1].department, None);
+
+        let mut output = Vec::new();
+        write_text(&mut output, &histories, &Options::default()).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.contains("Position: Engineer\nDepartment: Platform\nStart Date: 01/01/2020\n"));
+        assert_eq!(text.matches("Department:").count(), 1);
+    }
+
+    #[test]
+    fn expand_input_glob_finds_sorted_matches() {
+        let directory = std::env::temp_dir().join(format!("work_history_glob_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        for name in ["history_2021.csv", "history_2020.csv", "notes.txt"] {
+            std::fs::write(directory.join(name), HEADER).unwrap();
+        }
+
+        let pattern = directory.join("history_*.csv");
+        let paths = expand_input_glob(pattern.to_str().unwrap()).unwrap();
+        assert_eq!(paths, [directory.join("history_2020.csv"), directory.join("history_2021.csv")]);
+
+        let pattern = directory.join("missing_*.csv");
+        let err = expand_input_glob(pattern.to_str().unwrap()).unwrap_err();
+        assert!(err.to_string().starts_with("No input files match"));
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn reversed_dates_are_rejected_by_default() {
+        let data = format!("{}{}\n", HEADER, "Acme,Engineer,01/01/2021,01/01/2020,\"Springfield, IL\",Jane,Build,Growth");
+
+        let err = read_work_histories(data.as_bytes(), &Options::default(), &mut Vec::new()).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<InputError>(),
+            Some(&InputError::DateOrder {
+                row: 2,
+                start: "01/01/2021".to_string(),
+                end: "01/01/2020".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn fix_dates_swaps_reversed_dates_with_a_warning() {
+        let data = format!(
+            "{}{}\n{}\n",
+            HEADER,
+            "Acme,Engineer,01/01/2021,01/01/2020,\"Springfield, IL\",Jane,Build,Growth",
+            "Other,Engineer,01/01/2018,01/01/2019,\"Springfield, IL\",Jane,Build,Growth"
+        );
+        let options = Options { fix_dates: true, ..Options::default() };
+        let mut warnings = Vec::new();
+
+        let histories = read_work_histories(data.as_bytes(), &options, &mut warnings).unwrap();
+        assert_eq!(histories[0].start_date, Some(parse_date("01/01/2020").unwrap()));
+        assert_eq!(histories[0].end_date, Some(parse_date("01/01/2021").unwrap()));
+        assert_eq!(warnings, ["Row 2: start date 01/01/2021 is after end date 01/01/2020; swapped them"]);
+
+        // Correctly ordered rows are untouched
+        assert_eq!(histories[1].start_date, Some(parse_date("01/01/2018").unwrap()));
+        assert_eq!(histories[1].end_date, Some(parse_date("01/01/2019").unwrap()));
+    }
+
+    #[test]
+    fn repeated_header_rows_are_dropped_and_counted() {
+        let row = "Acme,Engineer,01/01/2020,01/01/2021,\"Springfield, IL\",Jane,Build,Growth\n";
+        let data = format!("{}{}{}{}{}", HEADER, row, HEADER, row, HEADER);
+        let options = Options { first_line_headers_only: true, ..Options::default() };
+        let mut warnings = Vec::new();
+
+        let histories = read_work_histories(data.as_bytes(), &options, &mut warnings).unwrap();
+        assert_eq!(histories.len(), 2);
+        assert_eq!(warnings, ["Dropped 2 repeated header rows"]);
+
+        let data = format!("{}{}{}", HEADER, row, HEADER);
+        let mut warnings = Vec::new();
+        read_work_histories(data.as_bytes(), &options, &mut warnings).unwrap();
+        assert_eq!(warnings, ["Dropped 1 repeated header row"]);
+
+        // Without the flag the repeated header is parsed as data and fails on its dates
+        assert!(read_work_histories(data.as_bytes(), &Options::default(), &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn cols_directive_maps_columns_in_declared_order() {
+        let data = "#cols: title,company,start,end,address\n\
+            Engineer,Acme,01/01/2020,Present,\"Springfield, IL\"\n";
+        let options = Options { cols_directive: true, ..Options::default() };
+
+        let histories = read_work_histories(data.as_bytes(), &options, &mut Vec::new()).unwrap();
+        assert_eq!(histories.len(), 1);
+        assert_eq!(histories[0].company, "Acme");
+        assert_eq!(histories[0].position, "Engineer");
+        assert_eq!(histories[0].location, "Springfield, IL");
+    }
+
+    #[test]
+    fn cols_directive_falls_back_to_the_header_row() {
+        let data = format!("{}Acme,Engineer,01/01/2020,Present,\"Springfield, IL\",Jane,Build,\n", HEADER);
+        let options = Options { cols_directive: true, ..Options::default() };
+
+        let histories = read_work_histories(data.as_bytes(), &options, &mut Vec::new()).unwrap();
+        assert_eq!(histories.len(), 1);
+        assert_eq!(histories[0].company, "Acme");
+    }
+
+    #[test]
+    fn cols_directive_keeps_file_row_numbers() {
+        let data = "#cols: company,title,start,end\nAcme,Engineer,01/01/2020,Present\nOther,Engineer,bad,Present\n";
+        let options = Options { cols_directive: true, ..Options::default() };
+
+        let err = read_work_histories(data.as_bytes(), &options, &mut Vec::new()).unwrap_err();
+        assert_eq!(err.to_string(), "Row 3: Failed to parse date: bad");
+    }
+
+    #[test]
+    fn rows_without_any_dates_are_undated() {
+        let data = format!(
+            "{}{}\n{}\n",
+            HEADER,
+            "Food Bank,Volunteer,,,\"Springfield, IL\",Jane,Sorted donations,",
+            "Acme,Engineer,,Present,\"Springfield, IL\",Jane,Build,"
+        );
+
+        let err = read_work_histories(data.as_bytes(), &Options::default(), &mut Vec::new()).unwrap_err();
+        assert_eq!(err.to_string(), "Row 3: Failed to parse date: ");
+
+        let data = format!("{}{}\n", HEADER, "Food Bank,Volunteer,,,\"Springfield, IL\",Jane,Sorted donations,");
+        let histories = read_work_histories(data.as_bytes(), &Options::default(), &mut Vec::new()).unwrap();
+        assert!(histories[0].is_undated());
+        assert_eq!(histories[0].end_date, None);
+    }
+
+    #[test]
+    fn explain_records_each_normalization() {
+        let data = format!(
+            "{}{}\n{}\n",
+            HEADER,
+            "Acme,Engineer,03/15/2020,Present,\"123 Main St, Springfield, IL 62701\",Jane,Build,",
+            "Other,Engineer,01/01/2018,01/01/2019,\"Springfield, IL\",Jane,Build,"
+        );
+        let options = Options { explain: true, ..Options::default() };
+
+        let histories = read_work_histories(data.as_bytes(), &options, &mut Vec::new()).unwrap();
+        assert_eq!(
+            histories[0].changes,
+            [
+                "start date 03/15/2020 written as 03/01/2020",
+                "end date \"Present\" read as ongoing",
+                "address \"123 Main St, Springfield, IL 62701\" reduced to \"Springfield, IL\"",
+            ]
+        );
+        assert!(histories[1].changes.is_empty());
+
+        // Nothing is recorded unless asked for
+        let histories = read_work_histories(data.as_bytes(), &Options::default(), &mut Vec::new()).unwrap();
+        assert!(histories[0].changes.is_empty());
+    }
+
+    #[test]
+    fn display_name_column_is_read_when_present() {
+        let data = "Company,Display Name,Job Title,Start Date,End Date\n\
+            Alphabet Inc.,Google,Engineer,01/01/2020,Present\n\
+            Acme,,Engineer,01/01/2018,01/01/2020\n";
+        let histories = read_work_histories(data.as_bytes(), &Options::default(), &mut Vec::new()).unwrap();
+
+        assert_eq!(histories[0].company, "Alphabet Inc.");
+        assert_eq!(histories[0].display_company(), "Google");
+        assert_eq!(histories[1].display_name, None);
+        assert_eq!(histories[1].display_company(), "Acme");
+    }
+
+    #[test]
+    fn custom_terminator_splits_records() {
+        let data = "Company,Job Title,Start Date,End Date|Acme,Engineer,01/01/2020,Present|\
+            Other,Engineer,01/01/2018,01/01/2020|";
+        let options = Options { terminator: Some(csv::Terminator::Any(b'|')), ..Options::default() };
+
+        let histories = read_work_histories(data.as_bytes(), &options, &mut Vec::new()).unwrap();
+        let companies: Vec<&str> = histories.iter().map(|h| h.company.as_str()).collect();
+        assert_eq!(companies, ["Acme", "Other"]);
+    }
+
+    #[test]
+    fn employment_type_abbreviations_are_normalized() {
+        assert_eq!(normalize_employment_type("FT"), "Full-time");
+        assert_eq!(normalize_employment_type("full time"), "Full-time");
+        assert_eq!(normalize_employment_type("P/T"), "Part-time");
+        assert_eq!(normalize_employment_type("Contractor"), "Contract");
+        assert_eq!(normalize_employment_type("Seasonal"), "Seasonal");
+
+        let data = "Company,Job Title,Start Date,End Date,Type\n\
+            Acme,Engineer,01/01/2020,Present,FT\n\
+            Other,Engineer,01/01/2018,01/01/2020,\n";
+        let histories = read_work_histories(data.as_bytes(), &Options::default(), &mut Vec::new()).unwrap();
+        assert_eq!(histories[0].employment_type.as_deref(), Some("Full-time"));
+        assert_eq!(histories[1].employment_type, None);
+    }
+
+    #[test]
+    fn reference_contact_is_read_with_the_supervisor_as_name() {
+        let csv = "Company,Job Title,Start Date,End Date,Address,Supervisor Name,Description,Reason,Reference Contact\n\
+                   Acme,Engineer,01/01/2020,Present,\"Springfield, IL\",Jane Doe,Built things,,jane@acme.com\n\
+                   Beta,Engineer,01/01/2018,01/01/2020,\"Springfield, IL\",,Built things,,jane@acme\n\
+                   Gamma,Engineer,01/01/2016,01/01/2018,\"Springfield, IL\",Sam Roe,Built things,,\n";
+        let mut warnings = Vec::new();
+        let histories = read_work_histories(csv.as_bytes(), &Options::default(), &mut warnings).unwrap();
+
+        assert_eq!(histories[0].reference_name.as_deref(), Some("Jane Doe"));
+        assert_eq!(histories[0].reference_contact.as_deref(), Some("jane@acme.com"));
+        assert_eq!(histories[1].reference_name, None);
+        assert_eq!(histories[2].reference_contact, None);
+        assert_eq!(warnings, ["Row 3: reference contact \"jane@acme\" doesn't look like an email address"]);
+    }
+
+    #[test]
+    fn looks_like_email_is_loose() {
+        assert!(looks_like_email("jane.doe+work@mail.acme.com"));
+        assert!(!looks_like_email("jane@acme"));
+        assert!(!looks_like_email("@acme.com"));
+        assert!(!looks_like_email("jane doe@acme.com"));
+        assert!(!looks_like_email("jane@@acme.com"));
+        assert!(!looks_like_email("jane@acme..com"));
+    }
+
+    #[test]
+    fn http_urls_are_recognized_as_input() {
+        assert_eq!(input_url(Path::new("https://example.com/history.csv")), Some("https://example.com/history.csv"));
+        assert_eq!(input_url(Path::new("HTTP://example.com/history.csv")), Some("HTTP://example.com/history.csv"));
+        assert_eq!(input_url(Path::new("history.csv")), None);
+        assert_eq!(input_url(Path::new("ftp://example.com/history.csv")), None);
+    }
+
+    #[test]
+    fn fetch_failures_name_the_url() {
+        // Nothing listens on port 1
+        let err = fetch_input("http://127.0.0.1:1/history.csv").unwrap_err();
+        assert!(err.to_string().contains("Failed to fetch http://127.0.0.1:1/history.csv"));
+    }
+
+    #[test]
+    fn extract_zip_code_reads_five_and_nine_digit_zips() {
+        assert_eq!(extract_zip_code("123 Main St, Springfield, IL 62701").as_deref(), Some("62701"));
+        assert_eq!(extract_zip_code("123 Main St, Springfield, IL 62701-1234").as_deref(), Some("62701-1234"));
+        assert_eq!(extract_zip_code("Springfield, IL 627011234").as_deref(), Some("62701-1234"));
+        assert_eq!(extract_zip_code("Springfield, IL, 62701,").as_deref(), Some("62701"));
+    }
+
+    #[test]
+    fn extract_zip_code_ignores_addresses_without_one() {
+        assert_eq!(extract_zip_code("Springfield, IL"), None);
+        assert_eq!(extract_zip_code("Remote"), None);
+        assert_eq!(extract_zip_code(""), None);
+        assert_eq!(extract_zip_code("Suite 12345B"), None);
+        assert_eq!(extract_zip_code("London SW1A 2AA"), None);
+    }
+
+    #[test]
+    fn missing_required_columns_are_listed() {
+        let csv = "Company,Job Title,Start Date\nAcme,Engineer,01/01/2020\n";
+        let options = Options {
+            required_columns: vec![Field::Company, Field::Position, Field::StartDate, Field::EndDate, Field::Salary],
+            ..Options::default()
+        };
+
+        let err = read_work_histories(csv.as_bytes(), &options, &mut Vec::new()).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<InputError>(),
+            Some(&InputError::MissingColumns { columns: vec!["End Date".to_string(), "Salary".to_string()] })
+        );
+        assert_eq!(err.to_string(), "The input is missing required columns: End Date, Salary");
+    }
+
+    #[test]
+    fn invalid_utf8_reports_row_byte_and_column() {
+        // "Café" saved as Latin-1
+        let mut data = HEADER.as_bytes().to_vec();
+        data.extend_from_slice(b"Acme,Engineer,01/01/2020,01/01/2021,Springfield,Jane,Build,Growth\n");
+        data.extend_from_slice(b"Caf\xe9,Barista,01/01/2021,Present,Springfield,Jane,Brew,\n");
+
+        let err = read_work_histories(&data[..], &Options::default(), &mut Vec::new()).unwrap_err();
+        let byte = (HEADER.len() + 66) as u64;
+        assert_eq!(err.downcast_ref::<InputError>(), Some(&InputError::InvalidUtf8 { row: 3, byte, column: 1 }));
+        assert!(err.to_string().contains("not valid UTF-8"));
+    }
+
+    #[test]
+    fn round_dates_to_month_sets_the_day_to_one() {
+        let data = format!(
+            "{}{}\n{}\n",
+            HEADER,
+            "Acme,Engineer,03/15/2020,01/20/2021,Springfield,Jane,Build,Growth",
+            "Beta,Engineer,03/02/2020,Present,Springfield,Jane,Build,"
+        );
+        let options = Options { round_dates_to_month: true, ..Options::default() };
+
+        let histories = read_work_histories(data.as_bytes(), &options, &mut Vec::new()).unwrap();
+        assert_eq!(histories[0].start_date, NaiveDate::from_ymd_opt(2020, 3, 1));
+        assert_eq!(histories[0].end_date, NaiveDate::from_ymd_opt(2021, 1, 1));
+        assert_eq!(format_date(histories[0].start_date.unwrap()), "03/01/2020");
+        // Both jobs now start on the same day and last the same so far
+        assert_eq!(histories[0].start_date, histories[1].start_date);
+        let today = NaiveDate::from_ymd_opt(2021, 1, 1).unwrap();
+        assert_eq!(histories[0].tenure_months(today), histories[1].tenure_months(today));
+
+        let histories = read_work_histories(data.as_bytes(), &Options::default(), &mut Vec::new()).unwrap();
+        assert_eq!(histories[0].start_date, NaiveDate::from_ymd_opt(2020, 3, 15));
+    }
+
+    #[test]
+    fn null_date_sentinels_are_read_as_blank() {
+        for null in DEFAULT_NULL_DATES {
+            let data = format!(
+                "{}Acme,Engineer,01/01/2020,{},Springfield,Jane,Build,\nFood Bank,Volunteer,{},{},Springfield,Jane,Sorted,\n",
+                HEADER, null, null, null
+            );
+            let histories = read_work_histories(data.as_bytes(), &Options::default(), &mut Vec::new()).unwrap();
+            assert_eq!(histories[0].end_date, None, "{}", null);
+            assert!(histories[1].is_undated(), "{}", null);
+        }
+
+        // A custom list replaces the defaults
+        let data = format!("{}Acme,Engineer,01/01/2020,unknown,Springfield,Jane,Build,\n", HEADER);
+        let options = Options { null_dates: Some(vec!["Unknown".to_string()]), ..Options::default() };
+        let histories = read_work_histories(data.as_bytes(), &options, &mut Vec::new()).unwrap();
+        assert_eq!(histories[0].end_date, None);
+        let data = format!("{}Acme,Engineer,01/01/2020,TBD,Springfield,Jane,Build,\n", HEADER);
+        assert!(read_work_histories(data.as_bytes(), &options, &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn spreadsheet_rows_are_read_like_csv() {
+        let mut range = Range::new((0, 0), (1, 4));
+        for (column, header) in ["Company", "Job Title", "Start Date", "End Date", "Address"].into_iter().enumerate() {
+            range.set_value((0, column as u32), Data::String(header.to_string()));
+        }
+        range.set_value((1, 0), Data::String("Acme, Inc.".to_string()));
+        range.set_value((1, 1), Data::String("Engineer".to_string()));
+        // 43845 is 01/15/2020 as an Excel serial date
+        range.set_value((1, 2), Data::DateTime(ExcelDateTime::new(43845.0, ExcelDateTimeType::DateTime, false)));
+        range.set_value((1, 3), Data::String("Present".to_string()));
+        range.set_value((1, 4), Data::String("Springfield, IL".to_string()));
+
+        let csv = sheet_to_csv(&range, DateMode::Lenient).unwrap();
+        let histories = read_work_histories(csv.as_slice(), &Options::default(), &mut Vec::new()).unwrap();
+        assert_eq!(histories.len(), 1);
+        assert_eq!(histories[0].company, "Acme, Inc.");
+        assert_eq!(histories[0].start_date, NaiveDate::from_ymd_opt(2020, 1, 15));
+        assert_eq!(histories[0].end_date, None);
+        assert_eq!(histories[0].location, "Springfield, IL");
+
+        let options = Options { date_mode: DateMode::IsoStrict, ..Options::default() };
+        let csv = sheet_to_csv(&range, options.date_mode).unwrap();
+        let histories = read_work_histories(csv.as_slice(), &options, &mut Vec::new()).unwrap();
+        assert_eq!(histories[0].start_date, NaiveDate::from_ymd_opt(2020, 1, 15));
+
+        assert_eq!(cell_text(&Data::Float(85000.0), DateMode::Lenient), "85000");
+        assert!(is_xlsx(Path::new("history.XLSX")));
+        assert!(!is_xlsx(Path::new("history.csv")));
+    }
+
+    #[test]
+    fn validate_emails_checks_every_contact_column() {
+        let csv = "Company,Job Title,Start Date,End Date,Supervisor Name,Reference Contact\n\
+                   Acme,Engineer,01/01/2020,Present,jane@acme,bob@acme.com\n\
+                   Beta,Engineer,01/01/2018,01/01/2020,Jane Doe,jane.doe@beta.com\n";
+
+        let mut warnings = Vec::new();
+        read_work_histories(csv.as_bytes(), &Options::default(), &mut warnings).unwrap();
+        assert!(warnings.is_empty());
+
+        let options = Options { validate_emails: true, ..Options::default() };
+        let mut warnings = Vec::new();
+        read_work_histories(csv.as_bytes(), &options, &mut warnings).unwrap();
+        assert_eq!(warnings, ["Row 2: supervisor name \"jane@acme\" doesn't look like an email address"]);
+
+        let options = Options { validate_emails: true, strict: true, ..Options::default() };
+        let err = read_work_histories(csv.as_bytes(), &options, &mut Vec::new()).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<InputError>(),
+            Some(&InputError::InvalidEmail {
+                row: 2,
+                column: "Supervisor Name".to_string(),
+                value: "jane@acme".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn collapse_ws_handles_tabs_spaces_and_newlines() {
+        assert_eq!(collapse_ws("Acme\t\tCorp"), "Acme Corp");
+        assert_eq!(collapse_ws("  Senior    Engineer "), "Senior Engineer");
+        assert_eq!(collapse_ws("Built  the API\r\n\n  \t\nLed \t the team\n"), "Built the API\nLed the team");
+        assert_eq!(collapse_ws(" \t "), "");
+    }
+
+    #[test]
+    fn collapse_whitespace_applies_at_parse_time() {
+        let data = format!("{}\"Acme   Corp\",Senior\tEngineer,01/01/2020,Present,\"Springfield, IL\",Jane,Build,\n", HEADER);
+
+        let histories = read_work_histories(data.as_bytes(), &Options::default(), &mut Vec::new()).unwrap();
+        assert_eq!(histories[0].company, "Acme   Corp");
+
+        let options = Options { collapse_whitespace: true, ..Options::default() };
+        let histories = read_work_histories(data.as_bytes(), &options, &mut Vec::new()).unwrap();
+        assert_eq!(histories[0].company, "Acme Corp");
+        assert_eq!(histories[0].position, "Senior Engineer");
+    }
+
+    #[test]
+    fn strip_excel_guard_removes_apostrophes_and_optionally_equals() {
+        assert_eq!(strip_excel_guard("'=Acme", false), "=Acme");
+        assert_eq!(strip_excel_guard("=Acme", false), "=Acme");
+        assert_eq!(strip_excel_guard("'=Acme", true), "Acme");
+        assert_eq!(strip_excel_guard("=Acme", true), "Acme");
+        assert_eq!(strip_excel_guard("=\"00123\"", true), "00123");
+        assert_eq!(strip_excel_guard("Acme's", true), "Acme's");
+    }
+
+    #[test]
+    fn excel_guards_are_stripped_from_every_field_at_parse_time() {
+        let data = format!("{}'=Acme,=Engineer,'01/15/2020,Present,\"Springfield, IL\",Jane,'Built it,\n", HEADER);
+
+        let histories = read_work_histories(data.as_bytes(), &Options::default(), &mut Vec::new()).unwrap();
+        assert_eq!(histories[0].company, "=Acme");
+        assert_eq!(histories[0].position, "=Engineer");
+        assert_eq!(histories[0].start_date, NaiveDate::from_ymd_opt(2020, 1, 15));
+        assert_eq!(histories[0].responsibilities, "Built it");
+
+        let options = Options { strip_formula_prefix: true, ..Options::default() };
+        let histories = read_work_histories(data.as_bytes(), &options, &mut Vec::new()).unwrap();
+        assert_eq!(histories[0].company, "Acme");
+        assert_eq!(histories[0].position, "Engineer");
+    }
+
+    #[test]
+    fn iso_strict_accepts_only_yyyy_mm_dd() {
+        assert_eq!(parse_date_in("2020-01-02", DateMode::IsoStrict).unwrap(), NaiveDate::from_ymd_opt(2020, 1, 2).unwrap());
+        assert!(parse_date_in("01/02/2020", DateMode::IsoStrict).is_err());
+        assert!(parse_date_in("January 2020", DateMode::IsoStrict).is_err());
+        assert!(parse_date_in("2020-1-2", DateMode::IsoStrict).is_err());
+        assert!(parse_date_in("2020-02-30", DateMode::IsoStrict).is_err());
+        assert_eq!(parse_end_date_in("Present", DateMode::IsoStrict).unwrap(), None);
+        // Lenient parsing is unchanged
+        assert!(parse_date_in("2020-01-02", DateMode::Lenient).is_err());
+    }
+
+    #[test]
+    fn iso_strict_rejects_slashed_dates_with_a_clear_error() {
+        let options = Options { date_mode: DateMode::IsoStrict, ..Options::default() };
+        let data = format!("{}Acme,Engineer,2020-01-15,Present,\"Springfield, IL\",Jane,Build,\n", HEADER);
+        let histories = read_work_histories(data.as_bytes(), &options, &mut Vec::new()).unwrap();
+        assert_eq!(histories[0].start_date, NaiveDate::from_ymd_opt(2020, 1, 15));
+
+        let data = format!("{}Acme,Engineer,2020-01-15,01/02/2020,\"Springfield, IL\",Jane,Build,\n", HEADER);
+        let err = read_work_histories(data.as_bytes(), &options, &mut Vec::new()).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<InputError>(),
+            Some(&InputError::NonIsoDate { row: 2, value: "01/02/2020".to_string() })
+        );
+        assert_eq!(
+            err.to_string(),
+            "Row 2: Date \"01/02/2020\" is not a YYYY-MM-DD date, which --date-mode iso-strict requires"
+        );
+    }
+
+    #[test]
+    fn day_first_reads_slashed_dates_as_dd_mm_yyyy() {
+        assert_eq!(parse_date_in("13/01/2020", DateMode::DayFirst).unwrap(), NaiveDate::from_ymd_opt(2020, 1, 13).unwrap());
+        assert_eq!(parse_date_in("01/12/2020", DateMode::DayFirst).unwrap(), NaiveDate::from_ymd_opt(2020, 12, 1).unwrap());
+        assert_eq!(parse_date_in("Jan 2020", DateMode::DayFirst).unwrap(), NaiveDate::from_ymd_opt(2020, 1, 1).unwrap());
+        // A month-first date with a day over 12 isn't silently accepted
+        assert!(parse_date_in("01/13/2020", DateMode::DayFirst).is_err());
+        // Month first stays the default
+        assert!(parse_date("13/01/2020").is_err());
+        assert_eq!(parse_date("01/12/2020").unwrap(), NaiveDate::from_ymd_opt(2020, 1, 12).unwrap());
+
+        let options = Options { date_mode: DateMode::DayFirst, ..Options::default() };
+        let data = format!("{}Acme,Engineer,13/01/2020,31/12/2021,\"Springfield, IL\",Jane,Build,\n", HEADER);
+        let histories = read_work_histories(data.as_bytes(), &options, &mut Vec::new()).unwrap();
+        assert_eq!(histories[0].start_date, NaiveDate::from_ymd_opt(2020, 1, 13));
+        assert_eq!(histories[0].end_date, NaiveDate::from_ymd_opt(2021, 12, 31));
+    }
+}
@@ -0,0 +1,62 @@
+//! Library behind the `csv_to_work_history_parser` command-line tool.
+//! 
+//! Reads work history entries from CSV, filters and sorts them, and writes
+//! them in the formatted text layout. See the binary's documentation for the
+//! input and output formats.
+
+pub mod anonymize;
+pub mod clock;
+pub mod columns;
+pub mod entry_filter;
+pub mod error;
+pub mod ffi;
+pub mod filters;
+pub mod gaps;
+pub mod history;
+pub mod input;
+pub mod options;
+pub mod output;
+pub mod sanity;
+pub mod skills;
+pub mod stats;
+
+pub use history::{DateFormat, WorkHistory};
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use crate::history::WorkHistory;
+    use crate::input::{parse_date, parse_end_date};
+
+    /// Header row of the original input layout.
+    pub const HEADER: &str = "Company,Job Title,Start Date,End Date,Address,Supervisor Name,Description,Reason\n";
+
+    /// Builds an entry with the given company and MM/DD/YYYY dates; an empty
+    /// start date makes it undated.
+    pub fn entry(company: &str, start: &str, end: &str) -> WorkHistory {
+        WorkHistory {
+            company: company.to_string(),
+            position: "Engineer".to_string(),
+            start_date: (!start.is_empty()).then(|| parse_date(start).unwrap()),
+            end_date: parse_end_date(end).unwrap(),
+            department: None,
+            location: "Springfield, IL".to_string(),
+            responsibilities: "Build things".to_string(),
+            salary: None,
+            skills: Vec::new(),
+            changes: Vec::new(),
+            display_name: None,
+            employment_type: None,
+            source_index: 0,
+            reference_name: None,
+            reference_contact: None,
+            postal_code: None,
+            keywords: Vec::new(),
+            company_url: None,
+        }
+    }
+
+    /// Builds a full argument list from the arguments after the program name.
+    pub fn args(list: &[&str]) -> Vec<String> {
+        std::iter::once("program").chain(list.iter().copied()).map(String::from).collect()
+    }
+}
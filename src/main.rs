@@ -15,17 +15,261 @@
 //! 
 //! # Usage
 //! ```bash
-//! csv_to_work_history_parser <input.csv> [output.txt]
+//! csv_to_work_history_parser [options] <input.csv> [output.txt]
+//! csv_to_work_history_parser [options] --input-glob <pattern> [output.txt]
+//! csv_to_work_history_parser [options] https://example.com/work_history.csv [output.txt]
 //! ```
+//! The input may be an `http://` or `https://` URL, which is downloaded and parsed in
+//! memory.
+//! An `.xlsx` input is read from its first sheet (or `--sheet`) as if it were a CSV.
 //! If output path is not provided, the file will be created in the current directory
 //! with the name "formatted_work_history.txt"
 //! 
+//! # Options
+//! - `--since-company <name>` - Only include jobs from the first (by start date) job at
+//!   `<name>` onward
+//! - `--verbose` - Print parse time and rows/second to stderr
+//! - `--strict` - Reject rows whose column count differs from the header
+//! - `--preview <n>` - Print the first `<n>` formatted entries to stdout without writing
+//!   a file
+//! - `--join-responsibilities <i,j,...>` - Join the non-empty values of these
+//!   (zero-based) columns with "; " to form the responsibilities, instead of using column
+//!   6 alone
+//! - `--only-current` - Only include jobs that are still ongoing
+//! - `--chronological-labels` - Keep entries newest first but label each by its position
+//!   in time, e.g. "Job #6 of 6 (most recent)", instead of "Work History N"
+//! - `--output-dir <dir>` - Write output into `<dir>` (a relative output path is resolved
+//!   against it)
+//! - `--split-per-entry` - Write each entry to its own file, `work_history_1.txt`,
+//!   `work_history_2.txt`, ..., in the output directory instead of one output file
+//! - `--include-salary` - Write a "Salary: $X" line from the optional Salary column
+//! - `--input-glob <pattern>` - Read and merge every file matching `<pattern>` (e.g.
+//!   `"history_*.csv"`) into one sorted output, instead of a single input file
+//! - `--hash` - Print the SHA-256 of each generated output file to stderr, in `sha256sum`
+//!   format
+//! - `--no-blank-lines` - Omit the empty line after each entry; the output then ends with
+//!   the last entry's final line
+//! - `--fix-dates` - Swap start and end dates that are in the wrong order, with a warning
+//!   per row, instead of erroring
+//! - `--present-label <text>` - Text written for the end date of ongoing jobs, instead of
+//!   "Present"
+//! - `--skill-experience` - Print the total experience per skill (from the
+//!   comma-separated Skills column) to stdout, e.g. "Python: 6 years, Rust: 4 years";
+//!   overlapping jobs count once and ongoing jobs count until today
+//! - `--first-line-headers-only` - Treat only the first row as the header and skip any
+//!   later row that exactly repeats it, as left behind by concatenating several CSV files
+//! - `--no-color` - Don't colorize warnings (yellow) and errors (red) on stderr; colors
+//!   are only used when stderr is a terminal and the `NO_COLOR` environment variable is
+//!   unset
+//! - `--format <text|locations|json|jsonl|prose|csv|table|timeline|ics|html>` - The
+//!   output format. Several comma-separated formats (e.g. `--format text,json`) write one
+//!   file each, named after the output path with each format's extension:
+//!   - `text` - The text entries (default)
+//!   - `locations` - Only the distinct locations worked at, sorted, one per line
+//!   - `json` - A JSON array of the entries
+//!   - `jsonl` - JSON Lines, one JSON object per entry per line
+//!   - `prose` - One prose paragraph per entry ("From 01/2020 to 03/2022,
+//!     I worked as ...")
+//!   - `csv` - CSV in the input layout, which reads back as the same entries
+//!   - `table` - An aligned ASCII table of company, position, dates, and location
+//!   - `timeline` - An ASCII timeline with a bar per job across a year axis
+//!   - `ics` - An iCalendar file with an all-day event per job (ongoing jobs end today)
+//!   - `html` - An HTML page with an `<article>` per entry
+//! - `--min-tenure <months>` - Drop entries lasting fewer than `<months>` whole months
+//!   (ongoing jobs count until today), reporting how many were dropped on stderr
+//! - `--tidy` - Capitalize the first letter of the responsibilities and end them with a
+//!   period (replacing stray trailing commas, semicolons, colons, or dashes); the input
+//!   is unchanged
+//! - `--company-sort-within-year` - Order entries by end year, newest first (ongoing jobs
+//!   first), and alphabetically by company within the same year
+//! - `--diff` - Print a unified diff from each existing output file to the new output,
+//!   then ask on stderr whether to write it
+//! - `--force` - With `--diff`, write without asking
+//! - `--cols-directive` - If the first line is a `#cols: company,title,start,end,...`
+//!   comment, map columns by the names it lists, and read every following row as data
+//! - `--ascii-only` - Transliterate every field of the output to ASCII, e.g. "Café" to
+//!   "Cafe" and smart quotes to plain quotes
+//! - `--separator <text>` - Write `<text>` (e.g. `"---"`) on its own line between entries
+//!   instead of the blank line; nothing is written after the last entry
+//! - `--count` - Print the number of entries left after filtering (e.g. with
+//!   `--only-current`) to stdout and exit without writing the output file
+//! - `--undated <bottom|top>` - Place undated entries (both dates empty) after the dated
+//!   entries (default) or before them
+//! - `--explain` - Print, per entry, the normalizations applied (date reformatting,
+//!   address reduction, trimming, `--tidy`, ...) to stderr
+//! - `--sanity-check` - Warn about likely typos in otherwise valid input: a career
+//!   spanning more than 60 years from the earliest start date to the latest end date
+//! - `--pretty` - Indent the output of `--format json` for reading by eye; it is compact
+//!   by default
+//! - `--title <text>` - Start the text output with a title line, underlined with `=`
+//! - `--subtitle <text>` - Write a subtitle line below the title, before the first entry
+//! - `--stats` - Print statistics about the filtered entries to stdout instead of writing
+//!   the output file: the number of entries and current jobs, the longest and average
+//!   responsibilities length, and entries whose responsibilities are empty or under 10
+//!   characters; as a JSON object with `--format json`
+//! - `--terminator <CRLF|LF|c>` - Record terminator of the input: `CRLF` (any of `\r`,
+//!   `\n`, or `\r\n`; the default), `LF` (only `\n`), or any single ASCII character
+//! - `--quote-style <always|necessary|never>` - Quote every field of `--format csv`, only
+//!   fields that need it (default), or none
+//! - `--include-type` - Write an "Employment Type:" line from the optional Type column,
+//!   with abbreviations such as "FT", "PT", and "Contractor" normalized
+//! - `--merge-adjacent` - Merge entries at the same company that are next to each other
+//!   after sorting (e.g. roles around a promotion) into one entry spanning both, with the
+//!   positions and responsibilities joined by "; "
+//! - `--anonymize` - Replace each distinct company with a consistent placeholder
+//!   ("Company A", "Company B", ...), every supervisor name with "Supervisor", and every
+//!   location with "City, ST", dropping reference contacts and keeping dates and
+//!   responsibilities, for sharing the output as a sample
+//! - `--sort-by <end|duration>` - Order entries by most recent end date (default, ongoing
+//!   jobs first) or by tenure, longest first, with ongoing jobs measured until today
+//! - `--show-duration` - Write a "Duration:" line (e.g. "2 years 3 months") after the end
+//!   date; ongoing jobs are measured until today
+//! - `--responsibilities-from-multiline` - Write each line of a multiline
+//!   responsibilities cell as a "- " bullet below "Responsibilities:", instead of joining
+//!   the lines with spaces
+//! - `--footer` - End the text output with a line such as "Generated by
+//!   csv_to_work_history_parser v1.0.0 on 2025-09-12 from input.csv"
+//! - `--keep-order-stable` - Break ties between entries with equal sort keys (e.g. the
+//!   same end date) by the order they appear in the input, across all input files,
+//!   instead of the order filtering left them in
+//! - `--table-width <n>` - Fit `--format table` into `<n>` characters by narrowing the
+//!   widest columns, truncating values that no longer fit with "..."
+//! - `--max-gap <months>` - Exit with an error naming each gap between jobs longer than
+//!   `<months>` whole months (after filtering; overlapping jobs cover each other, ongoing
+//!   jobs run until today)
+//! - `--include-reference` - Write a "Reference: <name>, <contact>" line from the
+//!   optional "Reference Contact" (phone or email) and "Reference Name" columns; the name
+//!   defaults to the supervisor
+//! - `--normalize-phone` - With `--include-reference`, write US phone numbers in the
+//!   reference contact as "(XXX) XXX-XXXX", leaving emails and other numbers as written
+//! - `--timeline-width <n>` - Scale the bars of `--format timeline` to `<n>` characters
+//!   (default 60)
+//! - `--create-dirs` - Create the output file's directory, including missing parent
+//!   directories, instead of failing when it doesn't exist
+//! - `--emit-template` - Write an example input CSV, with a header naming every
+//!   recognized column and one row of example values, to the path given (or stdout) and
+//!   exit; no input is read
+//! - `--include-zip` - Write a "Postal Code:" line after the location with the ZIP code
+//!   (5-digit or ZIP+4) at the end of the address; addresses without one get no line
+//! - `--self-check` - Read the input twice, once with its rows reversed, and exit with an
+//!   error naming the first differing line if the formatted output isn't identical;
+//!   nothing is written
+//! - `--icons` - Prefix the Company, Position, date, and Location lines of the text
+//!   format with an emoji (🏢, 💼, 📅, 📍), e.g. for README-style resumes
+//! - `--responsibilities-sort <alpha|length|none>` - With
+//!   `--responsibilities-from-multiline`, order each entry's bullets alphabetically
+//!   (ignoring case), by length (shortest first), or as written (default)
+//! - `--exclude-file <path>` - Drop entries at the companies listed in `<path>`, one per
+//!   line (matching the company or display name, ignoring case; blank lines and `#`
+//!   comments are skipped), reporting how many were excluded on stderr
+//! - `--normalize-punctuation` - Replace curly quotes, en and em dashes, and ellipses in
+//!   every field with ASCII punctuation (`'`, `"`, `-`, `--`, `...`), leaving other
+//!   characters alone
+//! - `--require-columns <names>` - Fail before reading any rows unless the input has each
+//!   of these comma-separated columns (any recognized header name, e.g.
+//!   `company,position,start,end`), listing the missing ones
+//! - `--toc` - Start the text output with a "Contents" list naming each entry's heading,
+//!   company, and dates, e.g. "- Work History 1: Acme Corp — 01/2020 – Present"; text
+//!   format only
+//! - `--round-dates-to-month` - Set every parsed date's day to 1, so sorting and
+//!   durations are month-granular and the output doesn't imply day precision
+//! - `--date-format <fmt>` - Render dates with a chrono format string instead of the
+//!   default, or `year` for just the year, e.g. "2020 – 2022" (a range within one year is
+//!   a single "2020")
+//! - `--post-command <cmd>` - Run `<cmd>` on each written file, with `{output}` replaced
+//!   by its path, e.g. `--post-command "pandoc {output} -o resume.pdf"`; fails if the
+//!   command does
+//! - `--null-dates <list>` - Comma-separated date values read as blank, so an end date of
+//!   "N/A" is ongoing and a row with no real dates is undated (default
+//!   `N/A,NA,-,TBD,None,00/00/0000`)
+//! - `--quiet` - Don't show the progress spinner or print the success message
+//! - `--numbered-responsibilities` - Split the responsibilities at semicolons and line
+//!   breaks and write them below the label as a numbered list ("1. ", "2. ", ...),
+//!   restarting for each entry
+//! - `--min-entries <n>` - Fail if fewer than `<n>` entries remain after filtering, e.g.
+//!   to catch an accidentally empty or over-filtered input in CI
+//! - `--company-color` - In HTML output, give each entry a light background color derived
+//!   from a hash of its company name, so entries at one company are grouped visually
+//! - `--flatten` - With `--format csv`, join cells spanning several lines (e.g. multiline
+//!   responsibilities) with " / ", so each record is one line for spreadsheet imports
+//! - `--include-keywords` - Write a "Keywords: a, b, c" line from the optional Keywords
+//!   column (comma-separated, duplicates dropped), for keyword-targeted resumes
+//! - `--sort-keywords` - Sort the `--include-keywords` list alphabetically
+//! - `--warnings-file <path>` - Write the warnings (e.g. swapped dates, repeated headers,
+//!   and `--sanity-check` findings) and the notes on what was filtered out or matched to
+//!   `<path>`, one per line, instead of stderr
+//! - `--sheet <name>` - Read the named sheet of an `.xlsx` input instead of the first one
+//! - `--validate-emails` - Warn about malformed email addresses in the Supervisor Name,
+//!   Reference Name, and Reference Contact columns, naming the row; with `--strict` they
+//!   are errors
+//! - `--collapse-whitespace` - Collapse runs of spaces and tabs inside every text field
+//!   to single spaces and trim each line, keeping line breaks (but not blank lines)
+//! - `--clipboard` - Copy the formatted output to the system clipboard; without an
+//!   `[output]` path nothing is written to a file. Fails if no clipboard is available
+//!   (e.g. headless)
+//! - `--start-numbering-at <n>` - Number the first "Work History" heading `<n>` instead
+//!   of 1, e.g. to continue an existing document; `--split-per-entry` files start at
+//!   `work_history_<n>.txt`
+//! - `--output-bom` - Start each output file with a UTF-8 byte order mark (EF BB BF), for
+//!   Windows tools such as Excel that expect one
+//! - `--aggregate-responsibilities <keep|only>` - Start the text output with a "Key
+//!   Responsibilities" section listing every entry's responsibilities, split at
+//!   semicolons and line breaks, deduplicated (ignoring case and a trailing period), and
+//!   sorted alphabetically (or by length with `--responsibilities-sort length`). `keep`
+//!   still lists responsibilities under each entry; `only` leaves them out.
+//! - `--entry-filter <expr>` (or `--filter`) - Keep only the entries matching an
+//!   expression such as `company contains "Acme" and duration > 12mo`: comparisons of
+//!   `company`, `position`, or `location` (`contains`, `=`, `!=`, ignoring case) and
+//!   `duration` (months, or years as `2y`), `start`, or `end` (a date, YYYY-MM-DD, or
+//!   `present`; `=`, `!=`, `<`, `<=`, `>`, `>=`), combined with `and`, `or`, `not`, and
+//!   parentheses. See the `entry_filter` module for the grammar.
+//! - `--strip-formula-prefix` - Remove a leading `=` from every cell, unquoting Excel's
+//!   `="..."` form, e.g. `="Acme"` reads as "Acme". A leading apostrophe (`'00123`) is
+//!   always removed.
+//! - `--position-sort-within-company` - With `--merge-adjacent`, join the merged
+//!   positions and responsibilities in order of their start dates, earliest first,
+//!   instead of output order
+//! - `--relative-dates` - Follow each end date with how long ago the job ended, measured
+//!   to today, e.g. "End Date: 03/01/2021 (ended 3 years ago)" or "End Date: Present
+//!   (ongoing)"
+//! - `--date-mode <lenient|day-first|iso-strict>` - Read dates as MM/DD/YYYY or
+//!   spelled-out months (`lenient`, the default), the same with DD/MM/YYYY (`day-first`),
+//!   or accept only YYYY-MM-DD and reject every other date, so that DD/MM and MM/DD can't
+//!   be confused (`iso-strict`). "Present" and blank end dates still mean an ongoing job.
+//! - `--day-first` - Read slashed dates as DD/MM/YYYY, so "13/01/2020" is 13 January 2020
+//!   and "01/12/2020" is 1 December; spelled-out months are read as usual. Same as
+//!   `--date-mode day-first`, and can't be combined with `--date-mode iso-strict`.
+//! - `--output-template-file <path>` - Write the output inside a document template, such
+//!   as an HTML page or LaTeX document: the formatted entries replace the first
+//!   `{{entries}}` in the file, and the text around it is written before and after them
+//!   unchanged (around each file with `--split-per-entry`). Needs a single format other
+//!   than `html`.
+//! - `--error-format <human|json>` - Report errors as readable messages (default) or as a
+//!   JSON object on stderr, e.g. `{"error":"date_parse","row":17,"value":"13/40/2020"}`
+//! 
+//! Arguments can also be kept in a file and passed as `@<file>`, which is replaced by the
+//! arguments in the file, one per line (put a flag and its value on separate lines). Blank
+//! lines and lines starting with `#` are skipped.
+//! 
 //! # Input CSV Format
 //! The expected CSV format is:
 //! ```text
 //! Company,Job Title,Start Date,End Date,Address,Supervisor Name,Description,Reason
 //! "Company Name",Position,MM/DD/YYYY,MM/DD/YYYY,"Address",Supervisor,"Description",Reason
 //! ```
+//! Dates may also spell out the month, as in "March 15, 2021" or "January 2020" (read as
+//! the 1st of the month). An empty end date, "Present", or "Current" marks a job that is
+//! still ongoing.
+//! A row with both dates empty is an undated entry (e.g. volunteer work): it is
+//! written without date lines and placed after the dated entries (see `--undated`).
+//! Columns are matched by header name where recognized, otherwise by position.
+//! An optional "Department" column is written as a "Department:" line after the
+//! position when it has a value. An optional "Salary" column is written with
+//! `--include-salary`. An optional "Display Name" column gives the company name
+//! to show (e.g. "Google" for "Alphabet Inc."); the JSON formats keep both. An
+//! optional "Website" (or "Company URL") column is written as a "Website:" line
+//! after the company and links the company name in HTML, with "https://" added
+//! when the URL has no scheme.
 //! 
 //! # Output Format
 //! The program generates a text file with entries formatted as:
@@ -33,8 +277,9 @@
 //! Work History N
 //! Company: Company Name
 //! Position: Job Title
+//! Department: Department (if present)
 //! Start Date: MM/YYYY
-//! End Date: MM/YYYY
+//! End Date: MM/YYYY (or Present)
 //! Location: City, State
 //! Responsibilities: Description
 //! ```
@@ -44,6 +289,9 @@
 //! - Invalid file paths
 //! - Malformed CSV data
 //! - Invalid date formats
+//! - Start dates after their end dates (or, with `--fix-dates`, a warning and swap)
+//! - Headers naming the same column twice, e.g. two "Company" columns
+//! - Columns required with `--require-columns` that the input doesn't have
 //! 
 //! # Example
 //! ```bash
@@ -54,208 +302,749 @@
 //! csv_to_work_history_parser work_history.csv
 //! ```
 
-use anyhow::{Context, Result, anyhow};
-use chrono::NaiveDate;
-use csv::ReaderBuilder;
-use std::fs::File;
-use std::io::Write;
+use anyhow::{anyhow, Context, Result};
+use arboard::Clipboard;
+use csv_to_work_history_parser::anonymize::anonymize;
+use csv_to_work_history_parser::error::error_to_json;
+use csv_to_work_history_parser::filters::{apply_filters, sort_entries};
+use csv_to_work_history_parser::gaps::{find_gaps, Gap};
+use csv_to_work_history_parser::history::{entry_count, file_count};
+use csv_to_work_history_parser::input::{
+    expand_input_glob, fetch_input, input_url, is_xlsx, read_work_histories, read_xlsx,
+};
+use csv_to_work_history_parser::options::{
+    expand_response_files, parse_expanded_args, ErrorFormat, Options, OutputFormat,
+};
+use csv_to_work_history_parser::output::{
+    create_output_directory, normalize_punctuation, output_directory, render_format_files, render_split_files,
+    render_text_file, sha256_hex, tidy_sentence, transliterate, write_output, write_template, UTF8_BOM,
+};
+use csv_to_work_history_parser::sanity::sanity_check;
+use csv_to_work_history_parser::skills::{format_skill_experience, skill_experience};
+use csv_to_work_history_parser::stats::{compute_stats, format_stats};
+use csv_to_work_history_parser::WorkHistory;
+use std::fs::{self, File};
+use owo_colors::OwoColorize;
+use std::io::{self, IsTerminal, Write};
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Instant;
 
-/// Represents a single work history entry with parsed and formatted fields.
+/// Validates that the input file exists and output path is valid.
+/// 
+/// # Arguments
+/// * `input_path` - Path to the input CSV file
+/// * `output_path` - Path where the output file will be written
+/// * `create_dirs` - Whether to create a missing output directory instead of failing
 /// 
-/// This struct contains the essential information extracted from a CSV record,
-/// with dates parsed into `NaiveDate` for proper chronological sorting and
-/// formatting.
-#[derive(Debug)]
-struct WorkHistory {
-    /// Name of the employer/company
-    company: String,
-    /// Job title/position held
-    position: String,
-    /// Employment start date
-    start_date: NaiveDate,
-    /// Employment end date
-    end_date: NaiveDate,
-    /// Formatted location (City, State)
-    location: String,
-    /// Description of job responsibilities
-    responsibilities: String,
+/// # Returns
+/// * `Result<()>` - Ok if validation passes, Error otherwise
+fn validate_paths(input_path: &Path, output_path: &Path, create_dirs: bool) -> Result<()> {
+    // Check input file exists (URLs are checked when they are fetched)
+    if input_url(input_path).is_none() && !input_path.exists() {
+        return Err(anyhow!("Input file not found: {}", input_path.display()));
+    }
+
+    // If output path has a parent directory, check it exists (or create it)
+    if let Some(parent) = output_path.parent() {
+        if !parent.as_os_str().is_empty() && !parent.exists() {
+            if create_dirs {
+                return create_output_directory(output_path);
+            }
+            return Err(anyhow!(
+                "Output directory not found: {} (use --create-dirs to create it)",
+                parent.display()
+            ));
+        }
+    }
+
+    Ok(())
 }
 
-/// Parses command line arguments and returns input and output file paths.
+/// Decides whether messages on stderr are colorized: only when stderr is a
+/// terminal, and neither `--no-color` nor the `NO_COLOR` environment variable
+/// is set.
 /// 
-/// # Returns
-/// * `Result<(PathBuf, PathBuf)>` - Tuple of (input_path, output_path)
+/// # Arguments
+/// * `no_color` - Whether `--no-color` was given
 /// 
-/// # Errors
-/// Returns an error if arguments are missing or invalid
-fn parse_args() -> Result<(PathBuf, PathBuf)> {
-    let args: Vec<String> = env::args().collect();
-    
-    if args.len() < 2 || args.len() > 3 {
-        return Err(anyhow!(
-            "Usage: {} <input_csv_file> [output_txt_file]\n\
-            Example: {} work_history.csv my_output.txt\n\
-            If output file is not specified, 'formatted_work_history.txt' will be created in the current directory",
-            args.get(0).unwrap_or(&String::from("program")),
-            args.get(0).unwrap_or(&String::from("program"))
-        ));
+/// # Returns
+/// * `bool` - True if colors should be used
+fn color_enabled(no_color: bool) -> bool {
+    !no_color && env::var_os("NO_COLOR").is_none() && io::stderr().is_terminal()
+}
+
+/// The warnings and notes about a run's input: printed on stderr as they are
+/// found, or collected for the `--warnings-file`.
+struct Report {
+    /// Whether to colorize the "Warning:" prefix on stderr
+    color: bool,
+    /// The collected lines, when they go to a warnings file
+    lines: Option<Vec<String>>,
+}
+
+impl Report {
+    /// Creates the report for a run with the given options.
+    fn new(options: &Options) -> Self {
+        Report {
+            color: color_enabled(options.no_color),
+            lines: options.warnings_file.as_ref().map(|_| Vec::new()),
+        }
     }
 
-    let input_path = PathBuf::from(&args[1]);
-    let output_path = if args.len() == 3 {
-        PathBuf::from(&args[2])
-    } else {
-        PathBuf::from("formatted_work_history.txt")
-    };
+    /// Reports a recoverable problem in the input, in yellow when colors are
+    /// enabled.
+    fn warn<E: Write>(&mut self, stderr: &mut E, warning: &str) -> io::Result<()> {
+        match &mut self.lines {
+            Some(lines) => {
+                lines.push(format!("Warning: {}", warning));
+                Ok(())
+            }
+            None if self.color => writeln!(stderr, "{} {}", "Warning:".yellow(), warning),
+            None => writeln!(stderr, "Warning: {}", warning),
+        }
+    }
+
+    /// Reports what was done with the input, e.g. how many entries a filter
+    /// dropped.
+    fn note<E: Write>(&mut self, stderr: &mut E, note: &str) -> io::Result<()> {
+        match &mut self.lines {
+            Some(lines) => {
+                lines.push(note.to_string());
+                Ok(())
+            }
+            None => writeln!(stderr, "{}", note),
+        }
+    }
 
-    Ok((input_path, output_path))
+    /// Writes the collected lines to the `--warnings-file`, one per line. The
+    /// file is written even when it is empty, so a stale report never lingers.
+    /// 
+    /// # Arguments
+    /// * `path` - The warnings file
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Ok if the file is written (or there is none), Error otherwise
+    fn write_file(&self, path: Option<&PathBuf>) -> Result<()> {
+        let (Some(path), Some(lines)) = (path, &self.lines) else {
+            return Ok(());
+        };
+        let contents: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+        fs::write(path, contents).with_context(|| format!("Failed to write {}", path.display()))
+    }
 }
 
-/// Validates that the input file exists and output path is valid.
+/// Copies formatted output to the system clipboard, for `--clipboard`.
 /// 
 /// # Arguments
-/// * `input_path` - Path to the input CSV file
-/// * `output_path` - Path where the output file will be written
+/// * `contents` - The formatted output
 /// 
 /// # Returns
-/// * `Result<()>` - Ok if validation passes, Error otherwise
-fn validate_paths(input_path: &PathBuf, output_path: &PathBuf) -> Result<()> {
-    // Check input file exists
-    if !input_path.exists() {
-        return Err(anyhow!("Input file not found: {}", input_path.display()));
-    }
+/// * `Result<()>` - Ok if the text is on the clipboard, Error if there is no
+///   clipboard (e.g. in a headless session)
+fn copy_to_clipboard(contents: &[u8]) -> Result<()> {
+    let mut clipboard = Clipboard::new()
+        .context("No clipboard is available (e.g. in a headless session); write the output to a file instead")?;
+    clipboard
+        .set_text(String::from_utf8_lossy(contents.strip_prefix(&UTF8_BOM).unwrap_or(contents)))
+        .context("Failed to copy the output to the clipboard")
+}
 
-    // If output path has a parent directory, check it exists
-    if let Some(parent) = output_path.parent() {
-        if !parent.as_os_str().is_empty() && !parent.exists() {
-            return Err(anyhow!("Output directory not found: {}", parent.display()));
+/// Asks a yes/no question on stderr and reads the answer from stdin.
+/// 
+/// # Arguments
+/// * `question` - The question, without the "[y/N]" suffix
+/// 
+/// # Returns
+/// * `Result<bool>` - True only if the answer starts with "y" or "Y"
+fn confirm(question: &str) -> Result<bool> {
+    eprint!("{} [y/N] ", question);
+    io::stderr().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(answer.trim_start().starts_with(['y', 'Y']))
+}
+
+/// Prints the normalizations applied to each entry to stderr, including the
+/// ones the writer will apply.
+/// 
+/// # Arguments
+/// * `work_histories` - The entries, with the changes recorded while reading
+/// * `options` - Output options, for the writer-stage changes
+fn explain(work_histories: &[WorkHistory], options: &Options) {
+    for history in work_histories {
+        let mut changes = history.changes.clone();
+        if options.tidy && tidy_sentence(&history.responsibilities) != history.responsibilities {
+            changes.push("responsibilities tidied".to_string());
+        }
+        let text = [&history.company, &history.position, &history.location, &history.responsibilities];
+        if options.ascii_only && !text.iter().all(|value| value.is_ascii()) {
+            changes.push("transliterated to ASCII".to_string());
+        }
+
+        if changes.is_empty() {
+            eprintln!("{}: no changes", history.company);
+        } else {
+            eprintln!("{}: {}", history.company, changes.join("; "));
         }
     }
+}
 
-    Ok(())
+/// Resolves the input files to read: every match of `--input-glob`, or the
+/// single input path.
+/// 
+/// # Arguments
+/// * `options` - Input/output paths and processing options
+/// 
+/// # Returns
+/// * `Result<Vec<PathBuf>>` - The input files, in the order they are read
+fn input_paths(options: &Options) -> Result<Vec<PathBuf>> {
+    match &options.input_glob {
+        Some(pattern) => expand_input_glob(pattern),
+        None => Ok(vec![options.input_path.clone()]),
+    }
 }
 
-/// Parses a date string in MM/DD/YYYY format into a NaiveDate.
+/// Reads and merges the entries of every input, in order.
 /// 
 /// # Arguments
-/// * `date` - A string slice containing the date in MM/DD/YYYY format
+/// * `options` - Input/output paths and processing options
+/// * `input_paths` - The CSV files (or URLs) to read
+/// * `warnings` - Collects warnings about recoverable problems in the input
 /// 
 /// # Returns
-/// * `Result<NaiveDate>` - The parsed date or an error with context
-fn parse_date(date: &str) -> Result<NaiveDate> {
-    NaiveDate::parse_from_str(date, "%m/%d/%Y")
-        .with_context(|| format!("Failed to parse date: {}", date))
+/// * `Result<Vec<WorkHistory>>` - The entries of all inputs, numbered in read order
+fn read_inputs(options: &Options, input_paths: &[PathBuf], warnings: &mut Vec<String>) -> Result<Vec<WorkHistory>> {
+    let mut work_histories = Vec::new();
+
+    for input_path in input_paths {
+        // Download a URL into memory, convert a workbook to CSV, or open the input file
+        let histories = match input_url(input_path) {
+            Some(url) => read_work_histories(fetch_input(url)?.as_slice(), options, warnings),
+            None if is_xlsx(input_path) => {
+                read_work_histories(read_xlsx(input_path, options.sheet.as_deref(), options.date_mode)?.as_slice(), options, warnings)
+            }
+            None => {
+                let file = File::open(input_path)
+                    .with_context(|| format!("Failed to open input file: {}", input_path.display()))?;
+                read_work_histories(file, options, warnings)
+            }
+        }
+        .with_context(|| format!("Failed to read {}", input_path.display()))?;
+        // Number entries across all inputs, in the order they were read
+        let offset = work_histories.len();
+        work_histories.extend(histories.into_iter().map(|mut history| {
+            history.source_index += offset;
+            history
+        }));
+    }
+
+    Ok(work_histories)
 }
 
-/// Extracts city and state from an address string.
+/// Checks that the output doesn't depend on the order of the input rows, for
+/// `--self-check`: the inputs are read twice, once with the rows reversed,
+/// and both are filtered, sorted, and formatted as usual.
 /// 
 /// # Arguments
-/// * `address` - A string slice containing the full address
+/// * `options` - Input/output paths and processing options
+/// * `input_paths` - The CSV files (or URLs) to read
 /// 
 /// # Returns
-/// * `String` - Formatted "City, State" or the original string if parsing fails
-fn extract_location(address: &str) -> String {
-    // If the address already looks like "City, State", return it as is
-    if address.matches(',').count() == 1 {
-        return address.to_string();
-    }
-
-    // Extract city and state from longer addresses
-    let parts: Vec<&str> = address.split(',').collect();
-    match parts.len() {
-        0 => String::new(),
-        1 => parts[0].trim().to_string(),
-        2 => format!("{}", parts[1].trim()),
-        _ => {
-            let state_part = parts.get(2)
-                .and_then(|s| s.trim().split_whitespace().next())
-                .unwrap_or("");
-            format!("{}, {}", parts[1].trim(), state_part)
+/// * `Result<()>` - Ok if both runs give identical output, Error naming the first differing line otherwise
+fn self_check(options: &Options, input_paths: &[PathBuf]) -> Result<()> {
+    let render = |reverse: bool| -> Result<String> {
+        let mut work_histories = read_inputs(options, input_paths, &mut Vec::new())?;
+        if reverse {
+            work_histories.reverse();
         }
+        let work_histories = apply_filters(work_histories, options, options.today(), &mut Vec::new())?;
+        let mut work_histories = sort_entries(work_histories, options);
+        rewrite_for_output(&mut work_histories, options);
+
+        let mut output = Vec::new();
+        write_output(&mut output, &work_histories, options)?;
+        Ok(String::from_utf8_lossy(&output).into_owned())
+    };
+
+    let (forward, reversed) = (render(false)?, render(true)?);
+    if let Some((line, expected, actual)) = first_difference(&forward, &reversed) {
+        return Err(anyhow!(
+            "Self-check failed: output depends on input row order (try --keep-order-stable)\n\
+             line {}: \"{}\" with the rows as written, \"{}\" with them reversed",
+            line,
+            expected,
+            actual
+        ));
     }
+
+    if !options.quiet {
+        eprintln!("Self-check passed: the output is the same with the input rows reversed");
+    }
+    Ok(())
 }
 
-/// Formats a NaiveDate into the MM/YYYY format.
+/// Rewrites the sorted entries' fields as requested for output: placeholders
+/// for `--anonymize`, then plain punctuation for `--normalize-punctuation`
+/// and ASCII for `--ascii-only`.
 /// 
 /// # Arguments
-/// * `date` - A NaiveDate to format
+/// * `work_histories` - The entries to rewrite in place, in output order
+/// * `options` - The output options
+fn rewrite_for_output(work_histories: &mut [WorkHistory], options: &Options) {
+    if options.anonymize {
+        anonymize(work_histories);
+    }
+    if options.normalize_punctuation {
+        for history in work_histories.iter_mut() {
+            history.rewrite_text(normalize_punctuation);
+        }
+    }
+    if options.ascii_only {
+        for history in work_histories {
+            history.rewrite_text(transliterate);
+        }
+    }
+}
+
+/// Finds the first line where two texts differ.
+/// 
+/// # Arguments
+/// * `expected` - The first text
+/// * `actual` - The second text
 /// 
 /// # Returns
-/// * `String` - The date formatted as MM/01/YYYY
-fn format_date(date: NaiveDate) -> String {
-    date.format("%m/01/%Y").to_string()
+/// * `Option<(usize, &str, &str)>` - The one-based line number and both
+///   versions of the line (empty past the end of a text), or `None` if the lines are identical
+fn first_difference<'a>(expected: &'a str, actual: &'a str) -> Option<(usize, &'a str, &'a str)> {
+    let (mut expected_lines, mut actual_lines) = (expected.lines(), actual.lines());
+    let mut line = 1;
+    loop {
+        match (expected_lines.next(), actual_lines.next()) {
+            (None, None) => return None,
+            (expected, actual) if expected != actual => {
+                return Some((line, expected.unwrap_or(""), actual.unwrap_or("")));
+            }
+            _ => line += 1,
+        }
+    }
 }
 
-/// Process the CSV file and write formatted output.
+/// Process the CSV files and write formatted output.
 /// 
 /// # Arguments
-/// * `input_path` - Path to the input CSV file
-/// * `output_path` - Path where the output file will be written
+/// * `options` - Input/output paths and processing options
+/// * `input_paths` - The CSV files to read and merge
+/// * `stderr` - Destination for warnings, notes, and checksums (stderr outside tests)
 /// 
 /// # Returns
-/// * `Result<()>` - Ok if processing succeeds, Error otherwise
-fn process_work_history(input_path: &PathBuf, output_path: &PathBuf) -> Result<()> {
-    // Open and configure CSV reader
-    let file = File::open(input_path)
-        .with_context(|| format!("Failed to open input file: {}", input_path.display()))?;
-    
-    let mut rdr = ReaderBuilder::new()
-        .has_headers(true)
-        .flexible(true)
-        .from_reader(file);
+/// * `Result<(Vec<PathBuf>, usize)>` - Paths of the files written (none for a
+///   preview) and the number of entries in the output
+fn process_work_history<E: Write>(
+    options: &Options,
+    input_paths: &[PathBuf],
+    stderr: &mut E,
+) -> Result<(Vec<PathBuf>, usize)> {
+    let output_path = &options.output_path;
 
-    let mut work_histories = Vec::new();
+    // Warnings and notes go to stderr as they are found, or are collected for
+    // the --warnings-file, which is written once the input has been filtered
+    let mut report = Report::new(options);
+    if let Some(pattern) = &options.input_glob {
+        report.note(stderr, &format!("Matched {} for {}", file_count(input_paths.len()), pattern))?;
+    }
+
+    if options.self_check {
+        return self_check(options, input_paths).map(|_| (Vec::new(), 0));
+    }
 
-    // Parse CSV records
-    for result in rdr.records() {
-        let record = result.context("Failed to read CSV record")?;
-        
-        let work_history = WorkHistory {
-            company: record[0].to_string(),
-            position: record[1].to_string(),
-            start_date: parse_date(&record[2])?,
-            end_date: parse_date(&record[3])?,
-            location: extract_location(&record[4]),
-            responsibilities: record[6].to_string(),
+    let parse_start = Instant::now();
+    let mut warnings = Vec::new();
+    let mut work_histories = read_inputs(options, input_paths, &mut warnings)?;
+
+    for warning in &warnings {
+        report.warn(stderr, warning)?;
+    }
+
+    if options.verbose {
+        let elapsed = parse_start.elapsed().as_secs_f64();
+        let rows_per_second = if elapsed > 0.0 {
+            work_histories.len() as f64 / elapsed
+        } else {
+            0.0
         };
-        
-        work_histories.push(work_history);
+        let rows = match work_histories.len() {
+            1 => "1 row".to_string(),
+            rows => format!("{} rows", rows),
+        };
+        report.note(stderr, &format!("Parsed {} in {:.3}s ({:.0} rows/s)", rows, elapsed, rows_per_second))?;
+    }
+
+    // Warn about likely typos before anything is filtered out
+    if options.sanity_check {
+        for warning in sanity_check(&work_histories, options.today()) {
+            report.warn(stderr, &warning)?;
+        }
+    }
+
+    // Apply the filtering options
+    let mut notes = Vec::new();
+    work_histories = apply_filters(work_histories, options, options.today(), &mut notes)?;
+    for note in &notes {
+        report.note(stderr, note)?;
+    }
+    report.write_file(options.warnings_file.as_ref())?;
+
+    // Fail on a gap between jobs longer than allowed
+    if let Some(max_gap) = options.max_gap {
+        let too_long: Vec<String> = find_gaps(&work_histories, options.today())
+            .iter()
+            .filter(|gap| gap.months > max_gap)
+            .map(Gap::describe)
+            .collect();
+        if !too_long.is_empty() {
+            return Err(anyhow!("Employment gap longer than --max-gap {} months: {}", max_gap, too_long.join("; ")));
+        }
     }
 
-    // Sort work histories by end date (most recent first)
-    work_histories.sort_by(|a, b| b.end_date.cmp(&a.end_date));
+    // Print only the number of remaining entries
+    if options.count {
+        println!("{}", work_histories.len());
+        return Ok((Vec::new(), 0));
+    }
+
+    // Print statistics instead of the entries
+    if options.stats {
+        let stats = compute_stats(&work_histories);
+        if options.format == OutputFormat::Json {
+            println!("{}", serde_json::to_string(&stats)?);
+        } else {
+            print!("{}", format_stats(&stats));
+        }
+        return Ok((Vec::new(), 0));
+    }
+
+    if options.skill_experience {
+        let totals = skill_experience(&work_histories, options.today());
+        println!("Skill experience: {}", format_skill_experience(&totals));
+    }
 
-    // Create output file
-    let mut output = File::create(output_path)
-        .with_context(|| format!("Failed to create output file: {}", output_path.display()))?;
+    if options.explain {
+        explain(&work_histories, options);
+    }
+
+    // Sort work histories by end date (ongoing jobs first, then most recent)
+    work_histories = sort_entries(work_histories, options);
+    rewrite_for_output(&mut work_histories, options);
 
-    // Write formatted work histories
-    for (index, history) in work_histories.iter().enumerate() {
-        writeln!(output, "Work History {}", index + 1)?;
-        writeln!(output, "Company: {}", history.company)?;
-        writeln!(output, "Position: {}", history.position)?;
-        writeln!(output, "Start Date: {}", format_date(history.start_date))?;
-        writeln!(output, "End Date: {}", format_date(history.end_date))?;
-        writeln!(output, "Location: {}", history.location)?;
-        writeln!(output, "Responsibilities: {}", history.responsibilities)?;
-        writeln!(output)?; // Empty line between entries
+    // Print the first entries instead of writing the output file
+    if options.preview.is_some() {
+        let mut preview = Vec::new();
+        write_output(&mut preview, &work_histories, options)?;
+        if options.hash {
+            writeln!(stderr, "{}  -", sha256_hex(&preview))?;
+        }
+        io::stdout().write_all(&preview)?;
+        return Ok((Vec::new(), 0));
     }
 
+    // Format everything in memory first, one file per entry if requested
+    let files = if options.split_per_entry {
+        render_split_files(output_directory(output_path), &work_histories, options)?
+    } else if !options.additional_formats.is_empty() {
+        render_format_files(output_path, &work_histories, options)?
+    } else {
+        vec![render_text_file(output_path, &work_histories, options)?]
+    };
+
+    // Copy the output for pasting, and stop there without an output path
+    if options.clipboard {
+        copy_to_clipboard(&files[0].contents)?;
+        if options.clipboard_only {
+            return Ok((Vec::new(), work_histories.len()));
+        }
+    }
+
+    let mut written = Vec::new();
+    for file in files {
+        // Show what would change and let the user decide whether to write
+        if options.diff {
+            let diff = file.diff_against_existing()?;
+            if diff.is_empty() {
+                writeln!(stderr, "No changes to {}", file.path.display())?;
+                continue;
+            }
+            print!("{}", diff);
+            if !options.force && !confirm(&format!("Write {}?", file.path.display()))? {
+                continue;
+            }
+        }
+
+        if options.hash {
+            writeln!(stderr, "{}  {}", sha256_hex(&file.contents), file.path.display())?;
+        }
+        file.write()?;
+        written.push(file.path);
+    }
+
+    Ok((written, work_histories.len()))
+}
+
+/// Runs the `--post-command` on a written file and reports its exit status.
+/// 
+/// The command is split on whitespace and run directly, without a shell;
+/// `{output}` in any argument is replaced by the file's path.
+/// 
+/// # Arguments
+/// * `command` - The command line, e.g. "pandoc {output} -o resume.pdf"
+/// * `path` - The file that was written
+/// * `quiet` - Don't report a successful run, for `--quiet`
+/// 
+/// # Returns
+/// * `Result<()>` - Ok if the command succeeds, Error if it can't be run or fails
+fn run_post_command(command: &str, path: &Path, quiet: bool) -> Result<()> {
+    let path = path.display().to_string();
+    let mut words = command.split_whitespace().map(|word| word.replace("{output}", &path));
+    let program = words.next().ok_or_else(|| anyhow!("The --post-command is empty"))?;
+
+    let status = Command::new(&program)
+        .args(words)
+        .status()
+        .with_context(|| format!("Failed to run --post-command {}", program))?;
+    if !status.success() {
+        return Err(anyhow!("--post-command {} failed on {} ({})", program, path, status));
+    }
+    if !quiet {
+        eprintln!("Ran {} on {} ({})", program, path, status);
+    }
     Ok(())
 }
 
-fn main() -> Result<()> {
+/// Runs the program with the given command line arguments.
+/// 
+/// # Arguments
+/// * `args` - The full argument list, including the program name, with the
+///   response files already expanded
+/// 
+/// # Returns
+/// * `Result<()>` - Ok if the run succeeds, Error otherwise
+fn run(args: &[String]) -> Result<()> {
     // Parse command line arguments
-    let (input_path, output_path) = parse_args()?;
+    let options = parse_expanded_args(args)?;
+
+    // Write the example input instead of converting one
+    if options.emit_template {
+        return match &options.template_path {
+            Some(path) => {
+                let mut template = Vec::new();
+                write_template(&mut template)?;
+                fs::write(path, template).with_context(|| format!("Failed to write {}", path.display()))?;
+                if !options.quiet {
+                    println!("Successfully created {}", path.display());
+                }
+                Ok(())
+            }
+            None => write_template(&mut io::stdout()),
+        };
+    }
 
     // Validate input/output paths
-    validate_paths(&input_path, &output_path)?;
+    let inputs = input_paths(&options)?;
+    for input_path in &inputs {
+        validate_paths(input_path, &options.output_path, options.create_dirs)?;
+    }
 
     // Process the work history
-    process_work_history(&input_path, &output_path)?;
+    let (written, entries) = process_work_history(&options, &inputs, &mut io::stderr())?;
+
+    if options.quiet {
+        // Nothing to report
+    } else if options.split_per_entry {
+        let directory = output_directory(&options.output_path);
+        println!("Successfully created {} in {}", file_count(written.len()), directory.display());
+    } else if options.clipboard_only {
+        println!("Copied {} to the clipboard", entry_count(entries));
+    } else if options.preview.is_none() && !options.count && !options.stats && !options.self_check {
+        if options.clipboard {
+            println!("Copied {} to the clipboard", entry_count(entries));
+        }
+        if written.is_empty() {
+            println!("Left {} unchanged", options.output_path.display());
+        } else {
+            let paths: Vec<String> = written.iter().map(|path| path.display().to_string()).collect();
+            println!("Successfully created {} ({})", paths.join(", "), entry_count(entries));
+        }
+    }
 
-    println!("Successfully created {}", output_path.display());
+    // Hand each written file to the post-processing command
+    if let Some(command) = &options.post_command {
+        for path in &written {
+            run_post_command(command, path, options.quiet)?;
+        }
+    }
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Expands the `@<file>` arguments once and runs the program with them.
+/// 
+/// # Arguments
+/// * `args` - The full argument list, including the program name
+/// 
+/// # Returns
+/// * `(Vec<String>, Result<()>)` - The expanded arguments (the given ones if
+///   a response file can't be read), which decide how an error is reported,
+///   and the result of the run
+fn expand_and_run(args: &[String]) -> (Vec<String>, Result<()>) {
+    match expand_response_files(args) {
+        Ok(expanded) => {
+            let result = run(&expanded);
+            (expanded, result)
+        }
+        Err(err) => (args.to_vec(), Err(err)),
+    }
+}
+
+fn main() -> Result<()> {
+    let (args, result) = expand_and_run(&env::args().collect::<Vec<String>>());
+
+    match result {
+        Err(err) if ErrorFormat::from_args(&args) == ErrorFormat::Json => {
+            eprintln!("{}", error_to_json(&err));
+            std::process::exit(1);
+        }
+        Err(err) if color_enabled(args.iter().any(|arg| arg == "--no-color")) => {
+            eprintln!("{} {:?}", "Error:".red(), err);
+            std::process::exit(1);
+        }
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn process_work_history_returns_the_number_of_entries_written() {
+        let directory = env::temp_dir().join(format!("work_history_count_{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let input_path = directory.join("in.csv");
+        fs::write(
+            &input_path,
+            "Company,Job Title,Start Date,End Date,Address,Supervisor Name,Description,Reason\n\
+             Acme,Engineer,01/01/2020,Present,\"Springfield, IL\",Jane,Build,\n\
+             Beta,Engineer,01/01/2018,01/01/2020,\"Springfield, IL\",Jane,Build,\n\
+             Gamma,Engineer,01/01/2016,01/01/2018,\"Springfield, IL\",Jane,Build,\n",
+        )
+        .unwrap();
+        let options = Options {
+            input_path: input_path.clone(),
+            output_path: directory.join("out.txt"),
+            since_company: Some("Beta".to_string()),
+            ..Options::default()
+        };
+
+        let (written, entries) = process_work_history(&options, &[input_path], &mut Vec::new()).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(written, [directory.join("out.txt")]);
+        assert_eq!(entries, 2);
+    }
+
+    #[test]
+    fn warnings_file_collects_the_warnings() {
+        let directory = env::temp_dir().join(format!("work_history_warnings_{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let input_path = directory.join("in.csv");
+        fs::write(
+            &input_path,
+            "Company,Job Title,Start Date,End Date,Address,Supervisor Name,Description,Reason\n\
+             Acme,Engineer,01/01/2021,01/01/2020,\"Springfield, IL\",Jane,Build,\n",
+        )
+        .unwrap();
+        let options = Options {
+            input_glob: Some(directory.join("*.csv").display().to_string()),
+            output_path: directory.join("out.txt"),
+            fix_dates: true,
+            min_tenure: Some(6),
+            warnings_file: Some(directory.join("warnings.txt")),
+            ..Options::default()
+        };
+
+        let mut stderr = Vec::new();
+        process_work_history(&options, &[input_path], &mut stderr).unwrap();
+        let report = fs::read_to_string(directory.join("warnings.txt")).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(
+            report,
+            format!(
+                "Matched 1 file for {}\n\
+                 Warning: Row 2: start date 01/01/2021 is after end date 01/01/2020; swapped them\n\
+                 Dropped 0 entries shorter than 6 months\n",
+                directory.join("*.csv").display()
+            )
+        );
+        assert_eq!(String::from_utf8(stderr).unwrap(), "");
+    }
+
+    /// Writes one entry for the company `Café “Best”` with `--ascii-only` in
+    /// the given format and returns the output.
+    fn ascii_only_output(format: OutputFormat, name: &str) -> String {
+        let directory = env::temp_dir().join(format!("work_history_ascii_{}_{}", name, std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let input_path = directory.join("in.csv");
+        fs::write(
+            &input_path,
+            "Company,Job Title,Start Date,End Date,Address,Supervisor Name,Description,Reason\n\
+             \"Café “Best”\",Engineer,01/01/2020,Present,\"Zürich, ZH\",Jane,Build,\n",
+        )
+        .unwrap();
+        let options = Options {
+            input_path: input_path.clone(),
+            output_path: directory.join(name),
+            format,
+            ascii_only: true,
+            ..Options::default()
+        };
+
+        process_work_history(&options, &[input_path], &mut Vec::new()).unwrap();
+        let output = fs::read_to_string(directory.join(name)).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+        output
+    }
+
+    #[test]
+    fn ascii_only_json_stays_valid() {
+        let output = ascii_only_output(OutputFormat::Json, "out.json");
+        assert!(output.is_ascii());
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(json[0]["company"], "Cafe \"Best\"");
+        assert_eq!(json[0]["location"], "Zurich, ZH");
+    }
+
+    #[test]
+    fn ascii_only_csv_quotes_the_transliterated_field() {
+        let output = ascii_only_output(OutputFormat::Csv, "out.csv");
+        assert!(output.is_ascii());
+        assert!(output.lines().nth(1).unwrap().starts_with("\"Cafe \"\"Best\"\"\",,Engineer,"));
+    }
+
+    #[test]
+    fn error_settings_can_come_from_a_response_file() {
+        let directory = env::temp_dir().join(format!("work_history_error_args_{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let response_file = directory.join("args.txt");
+        fs::write(&response_file, "--error-format\njson\n--no-color\n").unwrap();
+        let args: Vec<String> = ["program", &format!("@{}", response_file.display()), "missing.csv"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+
+        let (expanded, result) = expand_and_run(&args);
+        fs::remove_dir_all(&directory).unwrap();
+        assert!(result.is_err());
+        assert_eq!(ErrorFormat::from_args(&expanded), ErrorFormat::Json);
+        assert!(expanded.iter().any(|arg| arg == "--no-color"));
+    }
+}
@@ -0,0 +1,1449 @@
+//! Command line options and their parsing.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::format::{Item, StrftimeItems};
+use chrono::NaiveDate;
+use csv::{QuoteStyle, Terminator};
+use std::path::PathBuf;
+
+use crate::clock::{Clock, SystemClock};
+use crate::columns::Field;
+use crate::entry_filter::EntryFilter;
+use crate::history::{DateFormat, PRESENT_LABEL};
+use crate::output::{transliterate, ENTRIES_PLACEHOLDER};
+
+/// How errors are reported on stderr.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum ErrorFormat {
+    /// Human-readable messages (the default)
+    #[default]
+    Human,
+    /// One JSON object per error, for tooling
+    Json,
+}
+
+impl ErrorFormat {
+    /// Parses an `--error-format` value.
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "human" => Ok(ErrorFormat::Human),
+            "json" => Ok(ErrorFormat::Json),
+            _ => Err(anyhow!("Invalid value for --error-format: {} (expected human or json)", value)),
+        }
+    }
+
+    /// Finds the requested error format without fully parsing the arguments,
+    /// so that argument errors can be reported in that format too.
+    pub fn from_args(args: &[String]) -> Self {
+        args.iter()
+            .position(|arg| arg == "--error-format")
+            .and_then(|index| args.get(index + 1))
+            .and_then(|value| ErrorFormat::parse(value).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Layout of the generated output.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    /// The "Work History N" text entries (the default)
+    #[default]
+    Text,
+    /// Only the distinct locations, sorted, one per line
+    Locations,
+    /// A JSON array of all entries
+    Json,
+    /// One JSON object per entry per line (JSON Lines)
+    Jsonl,
+    /// One flowing paragraph per entry, for cover letters
+    Prose,
+    /// CSV in the input layout, which reads back as the same entries
+    Csv,
+    /// An aligned ASCII table of company, position, dates, and location
+    Table,
+    /// An ASCII bar chart of each job across a year axis
+    Timeline,
+    /// An iCalendar file with an all-day event per job
+    Ics,
+    /// A standalone HTML page with an `<article>` per entry
+    Html,
+}
+
+impl OutputFormat {
+    /// Extension of a file in this format, used to name each output when
+    /// several formats are written at once.
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Json => "json",
+            OutputFormat::Jsonl => "jsonl",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Ics => "ics",
+            OutputFormat::Html => "html",
+            OutputFormat::Text
+            | OutputFormat::Locations
+            | OutputFormat::Prose
+            | OutputFormat::Table
+            | OutputFormat::Timeline => "txt",
+        }
+    }
+
+    /// Parses a `--format` value.
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "text" => Ok(OutputFormat::Text),
+            "locations" => Ok(OutputFormat::Locations),
+            "json" => Ok(OutputFormat::Json),
+            "jsonl" => Ok(OutputFormat::Jsonl),
+            "prose" => Ok(OutputFormat::Prose),
+            "csv" => Ok(OutputFormat::Csv),
+            "table" => Ok(OutputFormat::Table),
+            "timeline" => Ok(OutputFormat::Timeline),
+            "ics" => Ok(OutputFormat::Ics),
+            "html" => Ok(OutputFormat::Html),
+            _ => Err(anyhow!(
+                "Invalid value for --format: {} (expected text, locations, json, jsonl, prose, csv, table, timeline, ics, or html)",
+                value
+            )),
+        }
+    }
+}
+
+/// How entries are ordered in the output.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum SortBy {
+    /// Most recent end date first, ongoing jobs at the top (the default)
+    #[default]
+    End,
+    /// Longest tenure first, with ongoing jobs measured until today
+    Duration,
+}
+
+impl SortBy {
+    /// Parses a `--sort-by` value.
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "end" => Ok(SortBy::End),
+            "duration" => Ok(SortBy::Duration),
+            _ => Err(anyhow!("Invalid value for --sort-by: {} (expected end or duration)", value)),
+        }
+    }
+}
+
+/// Where entries without any dates are placed in the output.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum UndatedPlacement {
+    /// After every dated entry (the default)
+    #[default]
+    Bottom,
+    /// Before every dated entry
+    Top,
+}
+
+impl UndatedPlacement {
+    /// Parses an `--undated` value.
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "bottom" => Ok(UndatedPlacement::Bottom),
+            "top" => Ok(UndatedPlacement::Top),
+            _ => Err(anyhow!("Invalid value for --undated: {} (expected bottom or top)", value)),
+        }
+    }
+}
+
+/// How responsibility bullets are ordered within an entry.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum ResponsibilitiesSort {
+    /// In the order they were written (the default)
+    #[default]
+    None,
+    /// Alphabetically, ignoring case
+    Alpha,
+    /// Shortest first
+    Length,
+}
+
+impl ResponsibilitiesSort {
+    /// Parses a `--responsibilities-sort` value.
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "none" => Ok(ResponsibilitiesSort::None),
+            "alpha" => Ok(ResponsibilitiesSort::Alpha),
+            "length" => Ok(ResponsibilitiesSort::Length),
+            _ => Err(anyhow!(
+                "Invalid value for --responsibilities-sort: {} (expected alpha, length, or none)",
+                value
+            )),
+        }
+    }
+}
+
+/// How input dates are read.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum DateMode {
+    /// MM/DD/YYYY or spelled-out months (the default)
+    #[default]
+    Lenient,
+    /// DD/MM/YYYY or spelled-out months (`--day-first`)
+    DayFirst,
+    /// Only YYYY-MM-DD, rejecting every other form
+    IsoStrict,
+}
+
+impl DateMode {
+    /// Parses a `--date-mode` value.
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "lenient" => Ok(DateMode::Lenient),
+            "day-first" => Ok(DateMode::DayFirst),
+            "iso-strict" => Ok(DateMode::IsoStrict),
+            _ => Err(anyhow!(
+                "Invalid value for --date-mode: {} (expected lenient, day-first, or iso-strict)",
+                value
+            )),
+        }
+    }
+
+    /// Switches to `mode` for a later `--date-mode` or `--day-first`, which
+    /// can't undo an earlier choice of another non-default mode.
+    /// 
+    /// # Arguments
+    /// * `mode` - The newly chosen mode
+    /// * `flag` - The argument choosing it, e.g. "--date-mode iso-strict"
+    /// * `chosen_by` - The argument that chose the current non-default mode,
+    ///   kept up to date for the error message
+    fn switch_to(self, mode: DateMode, flag: String, chosen_by: &mut Option<String>) -> Result<Self> {
+        if self != DateMode::Lenient && self != mode {
+            let earlier = chosen_by.as_deref().unwrap_or_default();
+            return Err(anyhow!("{} conflicts with {}; use only one", earlier, flag));
+        }
+        if mode != DateMode::Lenient {
+            chosen_by.get_or_insert(flag);
+        }
+        Ok(mode)
+    }
+}
+
+/// Whether entries keep their own responsibilities when they are also
+/// collected into a "Key Responsibilities" section.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AggregateResponsibilities {
+    /// Write the section and still list responsibilities under each entry
+    Keep,
+    /// Write the section instead of the per-entry responsibilities
+    Only,
+}
+
+impl AggregateResponsibilities {
+    /// Parses a `--aggregate-responsibilities` value.
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "keep" => Ok(AggregateResponsibilities::Keep),
+            "only" => Ok(AggregateResponsibilities::Only),
+            _ => Err(anyhow!(
+                "Invalid value for --aggregate-responsibilities: {} (expected keep or only)",
+                value
+            )),
+        }
+    }
+}
+
+/// Options controlling how the work history is read, filtered, and written.
+#[derive(Debug, Default)]
+pub struct Options {
+    /// Path to the input CSV file
+    pub input_path: PathBuf,
+    /// Path where the output file will be written
+    pub output_path: PathBuf,
+    /// Only keep entries from the first job at this company onward
+    pub since_company: Option<String>,
+    /// Print diagnostic details (such as parse timing) to stderr
+    pub verbose: bool,
+    /// Reject rows whose column count differs from the header
+    pub strict: bool,
+    /// Print this many formatted entries to stdout instead of writing the output file
+    pub preview: Option<usize>,
+    /// Column indices whose values are joined into the responsibilities
+    /// (defaults to the single description column when empty)
+    pub responsibility_columns: Vec<usize>,
+    /// Only keep jobs that are still ongoing
+    pub only_current: bool,
+    /// How errors are reported on stderr
+    pub error_format: ErrorFormat,
+    /// Label entries (still written newest first) by chronological position
+    pub chronological_labels: bool,
+    /// Directory the output is written to; a relative output path is resolved against it
+    pub output_dir: Option<PathBuf>,
+    /// Write each entry to its own numbered file instead of a single output file
+    pub split_per_entry: bool,
+    /// Write a "Salary:" line for entries that have one
+    pub include_salary: bool,
+    /// Glob pattern matching several input files to merge, used instead of `input_path`
+    pub input_glob: Option<String>,
+    /// Print the SHA-256 of the generated output to stderr
+    pub hash: bool,
+    /// Omit the empty line written after each entry
+    pub no_blank_lines: bool,
+    /// Swap start and end dates that are in the wrong order instead of erroring
+    pub fix_dates: bool,
+    /// Text written in place of the end date of an ongoing job (default "Present")
+    pub present_label: Option<String>,
+    /// Print total experience per skill from the Skills column
+    pub skill_experience: bool,
+    /// Skip rows after the first that repeat the header (for concatenated files)
+    pub first_line_headers_only: bool,
+    /// Never colorize warnings and errors on stderr
+    pub no_color: bool,
+    /// Clock giving the date ongoing jobs are measured to, instead of the
+    /// system clock (not a command line option; set a [`FixedClock`] to get
+    /// deterministic results)
+    /// 
+    /// [`FixedClock`]: crate::clock::FixedClock
+    pub clock: Option<Box<dyn Clock>>,
+    /// Layout of the generated output (the first one given to `--format`)
+    pub format: OutputFormat,
+    /// Further layouts from a comma-separated `--format`, each written to
+    /// the output path with the format's extension
+    pub additional_formats: Vec<OutputFormat>,
+    /// Drop entries shorter than this many months
+    pub min_tenure: Option<usize>,
+    /// Capitalize responsibilities and end them with a period in the output
+    pub tidy: bool,
+    /// Order entries by end year (newest first), then by company within each year
+    pub company_sort_within_year: bool,
+    /// Print a diff against the existing output and ask before overwriting it
+    pub diff: bool,
+    /// With `--diff`, write without asking
+    pub force: bool,
+    /// Read the column order from a leading `#cols:` comment line instead of a header row
+    pub cols_directive: bool,
+    /// Transliterate the output to ASCII
+    pub ascii_only: bool,
+    /// Line written between entries instead of a blank line
+    pub separator: Option<String>,
+    /// Print the number of entries left after filtering instead of writing output
+    pub count: bool,
+    /// Where entries without any dates are placed
+    pub undated: UndatedPlacement,
+    /// Print the normalizations applied to each entry to stderr
+    pub explain: bool,
+    /// Warn about likely typos, such as a career spanning more than 60 years
+    pub sanity_check: bool,
+    /// Indent JSON output
+    pub pretty: bool,
+    /// Title line written (underlined) at the top of the text output
+    pub title: Option<String>,
+    /// Subtitle line written below the title of the text output
+    pub subtitle: Option<String>,
+    /// Print summary statistics instead of writing output
+    pub stats: bool,
+    /// Record terminator of the input CSV (defaults to any newline)
+    pub terminator: Option<Terminator>,
+    /// Quoting of fields in `--format csv` (defaults to quoting only where necessary)
+    pub quote_style: Option<QuoteStyle>,
+    /// Write an "Employment Type:" line for entries that have one
+    pub include_type: bool,
+    /// Merge consecutive entries at the same company into one
+    pub merge_adjacent: bool,
+    /// Replace company names and locations with placeholders
+    pub anonymize: bool,
+    /// How entries are ordered
+    pub sort_by: SortBy,
+    /// Write a "Duration:" line with each entry's tenure
+    pub show_duration: bool,
+    /// Write each line of multiline responsibilities as a "- " bullet instead of joining them
+    pub multiline_bullets: bool,
+    /// Write a "Generated by ..." line after the last entry
+    pub footer: bool,
+    /// Break ties between equal sort keys by the order entries appear in the input
+    pub keep_order_stable: bool,
+    /// Total width `--format table` is narrowed to fit, if any
+    pub table_width: Option<usize>,
+    /// Longest allowed gap between jobs, in months, or `None` not to check
+    pub max_gap: Option<i32>,
+    /// Whether to write a "Reference:" line from the optional reference columns
+    pub include_reference: bool,
+    /// Whether to write US phone numbers in reference contacts as "(XXX) XXX-XXXX"
+    pub normalize_phone: bool,
+    /// Width of the bars of `--format timeline`, if not the default
+    pub timeline_width: Option<i32>,
+    /// Create the output directory (and its parents) if it doesn't exist
+    pub create_dirs: bool,
+    /// Write an example input CSV instead of converting one
+    pub emit_template: bool,
+    /// Where `--emit-template` writes the example CSV, or `None` for stdout
+    pub template_path: Option<PathBuf>,
+    /// Whether to write a "Postal Code:" line with the ZIP code from the address
+    pub include_zip: bool,
+    /// Check that the output doesn't depend on input row order instead of writing it
+    pub self_check: bool,
+    /// Prefix the company, position, date, and location lines with an emoji
+    pub icons: bool,
+    /// Order of the responsibility bullets of an entry
+    pub responsibilities_sort: ResponsibilitiesSort,
+    /// File listing companies (one per line) whose entries are dropped
+    pub exclude_file: Option<PathBuf>,
+    /// Replace curly quotes, dashes, and ellipses in every field with ASCII punctuation
+    pub normalize_punctuation: bool,
+    /// Fields the input must have a column for
+    pub required_columns: Vec<Field>,
+    /// Write a table of contents before the text entries
+    pub toc: bool,
+    /// Round every parsed date down to the first of its month
+    pub round_dates_to_month: bool,
+    /// chrono format for dates in ranges, e.g. "%Y" for years only (default "%m/%Y")
+    pub date_pattern: Option<String>,
+    /// External command run on each written file, with "{output}" replaced by its path
+    pub post_command: Option<String>,
+    /// Date cells treated as blank, e.g. "N/A" (default [`DEFAULT_NULL_DATES`])
+    pub null_dates: Option<Vec<String>>,
+    /// Don't show the progress spinner or the success message
+    pub quiet: bool,
+    /// Write responsibilities split at semicolons and line breaks as a numbered list
+    pub numbered_responsibilities: bool,
+    /// Fail unless at least this many entries remain after filtering
+    pub min_entries: Option<usize>,
+    /// Give each HTML article a background color derived from its company
+    pub company_colors: bool,
+    /// Join multiline CSV cells into one line with " / "
+    pub flatten: bool,
+    /// Write a "Keywords:" line with the entry's keywords
+    pub include_keywords: bool,
+    /// Sort the keywords alphabetically in the "Keywords:" line
+    pub sort_keywords: bool,
+    /// File the warnings are written to instead of stderr
+    pub warnings_file: Option<PathBuf>,
+    /// Sheet of an `.xlsx` input to read, instead of the first
+    pub sheet: Option<String>,
+    /// Check every email in the supervisor and reference columns, failing under `strict`
+    pub validate_emails: bool,
+    /// Collapse runs of spaces and tabs inside text fields to single spaces
+    pub collapse_whitespace: bool,
+    /// Copy the formatted output to the system clipboard
+    pub clipboard: bool,
+    /// Only copy to the clipboard: `clipboard` was given without an output path
+    pub clipboard_only: bool,
+    /// Number of the first "Work History N" heading (default 1)
+    pub start_numbering_at: Option<usize>,
+    /// Start each output file with a UTF-8 byte order mark
+    pub output_bom: bool,
+    /// Collect every entry's responsibilities into a "Key Responsibilities" section
+    /// before the text entries, keeping or replacing the per-entry lines
+    pub aggregate_responsibilities: Option<AggregateResponsibilities>,
+    /// Keep only the entries matching this `--entry-filter` expression
+    pub entry_filter: Option<EntryFilter>,
+    /// Also remove a leading "=" (Excel's `="..."` text guard) from every cell
+    pub strip_formula_prefix: bool,
+    /// With `merge_adjacent`, join the merged positions in start date order, earliest first
+    pub position_sort_within_company: bool,
+    /// Annotate each text entry's end date with how long ago it was, e.g. "(ended 3 years ago)"
+    pub relative_dates: bool,
+    /// How input dates are read; `DayFirst` reads slashed dates as DD/MM/YYYY
+    /// and `IsoStrict` accepts only YYYY-MM-DD
+    pub date_mode: DateMode,
+    /// Contents of the `--output-template-file` document template, whose
+    /// `{{entries}}` placeholder is replaced by the formatted entries
+    pub output_template: Option<String>,
+}
+
+/// Date cells treated as blank unless `--null-dates` says otherwise.
+pub const DEFAULT_NULL_DATES: [&str; 6] = ["N/A", "NA", "-", "TBD", "None", "00/00/0000"];
+
+impl Options {
+    /// Every requested output format, in the order given.
+    pub fn formats(&self) -> impl Iterator<Item = OutputFormat> + '_ {
+        std::iter::once(self.format).chain(self.additional_formats.iter().copied())
+    }
+
+    /// Text written in place of the end date of an ongoing job.
+    pub fn present_label(&self) -> &str {
+        self.present_label.as_deref().unwrap_or(PRESENT_LABEL)
+    }
+
+    /// Date cells treated as blank: an end date that is one of them is
+    /// ongoing, and a row whose dates are both blank is undated.
+    pub fn null_dates(&self) -> Vec<&str> {
+        match &self.null_dates {
+            Some(values) => values.iter().map(String::as_str).collect(),
+            None => DEFAULT_NULL_DATES.to_vec(),
+        }
+    }
+
+    /// The date ongoing jobs are measured to, from `clock` if set, otherwise
+    /// from the system clock.
+    pub fn today(&self) -> NaiveDate {
+        match &self.clock {
+            Some(clock) => clock.today(),
+            None => SystemClock.today(),
+        }
+    }
+
+    /// Date format for rendering date ranges, honoring the present label.
+    pub fn date_format(&self) -> DateFormat {
+        let format = self.date_pattern.as_deref().map_or_else(DateFormat::default, DateFormat::new);
+        let format = format.with_present_label(self.present_label());
+        if self.ascii_only {
+            return format.with_separator(" - ");
+        }
+        format
+    }
+}
+
+/// Parses a `--date-format` value: "year" for just the year, or any chrono
+/// format string.
+/// 
+/// # Arguments
+/// * `value` - The raw option value
+/// 
+/// # Returns
+/// * `Result<String>` - The chrono format string, or an error if it is invalid
+fn parse_date_pattern(value: &str) -> Result<String> {
+    if value.eq_ignore_ascii_case("year") {
+        return Ok("%Y".to_string());
+    }
+    if value.is_empty() || StrftimeItems::new(value).any(|item| item == Item::Error) {
+        return Err(anyhow!("Invalid value for --date-format: {} (expected a chrono format such as %m/%Y, or year)", value));
+    }
+    Ok(value.to_string())
+}
+
+/// Parses a `--post-command` value, which must name a program to run.
+/// 
+/// # Arguments
+/// * `value` - The raw option value, e.g. "pandoc {output} -o resume.pdf"
+/// 
+/// # Returns
+/// * `Result<String>` - The command, or an error if it is blank or starts with `{output}`
+fn parse_post_command(value: &str) -> Result<String> {
+    match value.split_whitespace().next() {
+        None => Err(anyhow!("Invalid value for --post-command: the command is empty")),
+        Some(program) if program.contains("{output}") => Err(anyhow!(
+            "Invalid value for --post-command: {} (expected a program to run first, e.g. \"open {{output}}\")",
+            value
+        )),
+        Some(_) => Ok(value.trim().to_string()),
+    }
+}
+
+/// Parses a `--terminator` value: "CRLF" (any of `\r`, `\n`, or `\r\n`,
+/// the csv crate's default), "LF" (only `\n`), or a single ASCII character.
+/// 
+/// # Arguments
+/// * `value` - The raw option value
+/// 
+/// # Returns
+/// * `Result<Terminator>` - The record terminator for the CSV reader
+fn parse_terminator(value: &str) -> Result<Terminator> {
+    match value {
+        "CRLF" | "crlf" => Ok(Terminator::CRLF),
+        "LF" | "lf" => Ok(Terminator::Any(b'\n')),
+        _ if value.len() == 1 && value.is_ascii() => Ok(Terminator::Any(value.as_bytes()[0])),
+        _ => Err(anyhow!(
+            "Invalid value for --terminator: {} (expected CRLF, LF, or a single ASCII character)",
+            value
+        )),
+    }
+}
+
+/// Parses a `--quote-style` value.
+/// 
+/// # Arguments
+/// * `value` - The raw option value: "always", "necessary", or "never"
+/// 
+/// # Returns
+/// * `Result<QuoteStyle>` - The quoting style for the CSV writer
+fn parse_quote_style(value: &str) -> Result<QuoteStyle> {
+    match value {
+        "always" => Ok(QuoteStyle::Always),
+        "necessary" => Ok(QuoteStyle::Necessary),
+        "never" => Ok(QuoteStyle::Never),
+        _ => Err(anyhow!(
+            "Invalid value for --quote-style: {} (expected always, necessary, or never)",
+            value
+        )),
+    }
+}
+
+/// Builds the usage message shown when arguments are missing or invalid.
+/// 
+/// # Arguments
+/// * `program` - Name the program was invoked as
+/// 
+/// # Returns
+/// * `String` - The usage message
+fn usage(program: &str) -> String {
+    format!(
+        "Usage: {} [options] <input_csv_file> [output_txt_file]\n\
+        \x20      {} [options] --input-glob <pattern> [output_txt_file]\n\
+        Example: {} work_history.csv my_output.txt\n\
+        If output file is not specified, 'formatted_work_history.txt' will be created in the current directory\n\
+        The input CSV file may also be an http:// or https:// URL\n\
+        An argument @<file> is replaced by the arguments in <file>, one per line (blank lines and # comments are skipped)\n\
+        \n\
+        Options:\n\
+        \x20 --since-company <name>  Only include jobs from the first job at <name> onward\n\
+        \x20 --verbose               Print parse timing and throughput to stderr\n\
+        \x20 --strict                Reject rows whose column count differs from the header\n\
+        \x20 --preview <n>           Print the first <n> formatted entries to stdout without writing a file\n\
+        \x20 --join-responsibilities <i,j,...>\n\
+        \x20                         Join the non-empty values of these column indices into the responsibilities\n\
+        \x20 --only-current          Only include ongoing jobs (empty or \"Present\" end date)\n\
+        \x20 --error-format <fmt>    Report errors as human (default) or json\n\
+        \x20 --chronological-labels  Label entries \"Job #N of T\" by chronological position\n\
+        \x20 --output-dir <dir>      Write output into <dir>\n\
+        \x20 --split-per-entry       Write work_history_1.txt, work_history_2.txt, ... instead of one file\n\
+        \x20 --include-salary        Write a \"Salary:\" line from the Salary column\n\
+        \x20 --input-glob <pattern>  Merge all input files matching <pattern> (e.g. \"history_*.csv\")\n\
+        \x20 --hash                  Print the SHA-256 of the generated output to stderr\n\
+        \x20 --no-blank-lines        Omit the empty line between entries\n\
+        \x20 --fix-dates             Swap start/end dates that are reversed instead of erroring\n\
+        \x20 --present-label <text>  Text written for the end date of ongoing jobs (default \"Present\")\n\
+        \x20 --skill-experience      Print total experience per skill from the Skills column\n\
+        \x20 --first-line-headers-only\n\
+        \x20                         Skip later rows that repeat the header (concatenated CSVs)\n\
+        \x20 --no-color              Never colorize warnings and errors (also honors NO_COLOR)\n\
+        \x20 --format <fmt>          Write text entries (default), the sorted distinct locations,\n\
+        \x20                         a json array, jsonl (one JSON object per line), prose, csv,\n\
+        \x20                         an aligned table, a timeline of bars across the years, or an\n\
+        \x20                         ics calendar with an event per job, or an html page;\n\
+        \x20                         several comma-separated formats write one file each\n\
+        \x20 --min-tenure <months>   Drop entries shorter than <months> months\n\
+        \x20 --tidy                  Capitalize responsibilities and end them with a period\n\
+        \x20 --company-sort-within-year\n\
+        \x20                         Sort by end year (newest first), then by company within a year\n\
+        \x20 --diff                  Print a diff against the existing output and ask before writing\n\
+        \x20 --force                 With --diff, write without asking\n\
+        \x20 --cols-directive        Read the column order from a leading \"#cols: a,b,...\" line\n\
+        \x20 --ascii-only            Transliterate accented letters, smart quotes, and dashes to ASCII\n\
+        \x20 --separator <text>      Write <text> on its own line between entries instead of a blank line\n\
+        \x20 --count                 Print the number of entries after filtering, without writing a file\n\
+        \x20 --undated <bottom|top>  Place entries with no dates at all after (default) or before the rest\n\
+        \x20 --explain               Print what was normalized in each entry to stderr\n\
+        \x20 --sanity-check          Warn about likely typos, e.g. a career spanning over 60 years\n\
+        \x20 --pretty                Indent the JSON of --format json\n\
+        \x20 --title <text>          Start the text output with <text>, underlined with \"=\"\n\
+        \x20 --subtitle <text>       Write <text> below the title, before the entries\n\
+        \x20 --stats                 Print entry statistics (as JSON with --format json) instead of writing a file\n\
+        \x20 --terminator <t>        Record terminator of the input: CRLF (any newline, default), LF, or one character\n\
+        \x20 --quote-style <style>   Quote --format csv fields always, where necessary (default), or never\n\
+        \x20 --include-type          Write an \"Employment Type:\" line from the Type column\n\
+        \x20 --merge-adjacent        Merge consecutive entries at the same company into one\n\
+        \x20 --anonymize             Replace companies with \"Company A\", \"Company B\", ... and locations with \"City, ST\"\n\
+        \x20 --sort-by <end|duration>\n\
+        \x20                         Order by most recent end date (default) or by longest tenure\n\
+        \x20 --show-duration         Write a \"Duration:\" line with the tenure of each entry\n\
+        \x20 --responsibilities-from-multiline\n\
+        \x20                         Write each line of a multiline responsibilities cell as a \"- \" bullet\n\
+        \x20 --footer                End the text output with a \"Generated by ... on <date> from <input>\" line\n\
+        \x20 --keep-order-stable     Break sort ties by input order\n\
+        \x20 --table-width <n>       Narrow --format table to at most <n> characters, truncating long values\n\
+        \x20 --max-gap <months>      Fail if any gap between jobs is longer than <months> months\n\
+        \x20 --include-reference     Write a \"Reference: <name>, <contact>\" line from the reference columns\n\
+        \x20 --normalize-phone       Write US phone numbers in references as \"(XXX) XXX-XXXX\"\n\
+        \x20 --timeline-width <n>    Scale --format timeline to <n> characters (default 60)\n\
+        \x20 --create-dirs           Create a missing output directory instead of failing\n\
+        \x20 --emit-template         Write an example input CSV to [output] (or stdout) and exit\n\
+        \x20 --include-zip           Write a \"Postal Code:\" line with the ZIP code from the address\n\
+        \x20 --self-check            Check that the output is the same with the rows reversed, then exit\n\
+        \x20 --icons                 Prefix the company, position, date, and location lines with emoji\n\
+        \x20 --responsibilities-sort <order>\n\
+        \x20                         Order responsibility bullets: alpha, length (shortest first), or none\n\
+        \x20 --exclude-file <path>   Drop entries at the companies listed in <path>, one per line\n\
+        \x20 --normalize-punctuation\n\
+        \x20                         Replace curly quotes, en/em dashes, and ellipses with ASCII\n\
+        \x20 --require-columns <names>\n\
+        \x20                         Fail unless the input has these comma-separated columns\n\
+        \x20 --toc                   Start the text output with a table of contents of the entries\n\
+        \x20 --round-dates-to-month  Set every parsed date's day to 1, for month-granular sorting\n\
+        \x20 --date-format <fmt>     Render dates with a chrono format, or \"year\" for just the year\n\
+        \x20 --post-command <cmd>    Run <cmd> on each written file, with {{output}} replaced by its path\n\
+        \x20 --null-dates <list>     Treat these comma-separated date values as blank\n\
+        \x20                         (default N/A, NA, -, TBD, None, 00/00/0000)\n\
+        \x20 --quiet                 Don't show a progress spinner or print the success message\n\
+        \x20 --numbered-responsibilities\n\
+        \x20                         Number the responsibilities, split at semicolons and line breaks\n\
+        \x20 --min-entries <n>       Fail if fewer than <n> entries remain after filtering\n\
+        \x20 --company-color         Color each HTML entry's background by its company (same company, same color)\n\
+        \x20 --flatten               Write each CSV record on one line, joining multiline cells with \" / \"\n\
+        \x20 --include-keywords      Write a \"Keywords:\" line from the Keywords column\n\
+        \x20 --sort-keywords         Sort the --include-keywords list alphabetically\n\
+        \x20 --warnings-file <path>  Write warnings to <path>, one per line, instead of stderr\n\
+        \x20 --sheet <name>          Read the named sheet of an .xlsx input instead of the first\n\
+        \x20 --validate-emails       Warn about malformed emails in the supervisor and reference columns\n\
+        \x20 --collapse-whitespace   Collapse runs of spaces and tabs inside each field to one space\n\
+        \x20 --clipboard             Copy the output to the clipboard; without [output], skip the file\n\
+        \x20 --start-numbering-at <n>\n\
+        \x20                         Number the first \"Work History\" heading <n> instead of 1\n\
+        \x20 --output-bom            Start each output file with a UTF-8 byte order mark (for Excel)\n\
+        \x20 --aggregate-responsibilities <mode>\n\
+        \x20                         List all responsibilities, deduplicated, before the entries:\n\
+        \x20                         keep (also under each entry) or only\n\
+        \x20 --entry-filter <expr>   Keep entries matching <expr>, e.g. 'company contains Acme and duration > 12mo'\n\
+        \x20 --strip-formula-prefix  Remove a leading \"=\" from cells, e.g. Excel's =\"00123\"\n\
+        \x20 --position-sort-within-company\n\
+        \x20                         With --merge-adjacent, join the positions earliest first\n\
+        \x20 --relative-dates        Add \"(ended 3 years ago)\" or \"(ongoing)\" after each end date\n\
+        \x20 --date-mode <mode>      Read dates as lenient (default), day-first, or iso-strict YYYY-MM-DD\n\
+        \x20 --day-first             Read slashed dates as DD/MM/YYYY instead of MM/DD/YYYY\n\
+        \x20 --output-template-file <path>\n\
+        \x20                         Put the output in place of {{{{entries}}}} in the template at <path>",
+        program, program, program
+    )
+}
+
+/// Parses a non-negative count given as an option value.
+/// 
+/// # Arguments
+/// * `flag` - The option the value belongs to, used in the error message
+/// * `value` - The raw option value
+/// 
+/// # Returns
+/// * `Result<usize>` - The parsed count or an error naming the option
+fn parse_count(flag: &str, value: &str) -> Result<usize> {
+    value
+        .parse()
+        .with_context(|| format!("Invalid value for {}: {} (expected a whole number)", flag, value))
+}
+
+/// Parses a non-negative number of months given as an option value.
+/// 
+/// # Arguments
+/// * `flag` - The option the value belongs to, used in the error message
+/// * `value` - The raw option value
+/// 
+/// # Returns
+/// * `Result<i32>` - The parsed months, or an error naming the option if the
+///   value isn't a whole number or doesn't fit
+fn parse_months(flag: &str, value: &str) -> Result<i32> {
+    parse_count(flag, value)?
+        .try_into()
+        .with_context(|| format!("Invalid value for {}: {} (expected a whole number)", flag, value))
+}
+
+/// Widest `--timeline-width` accepted.
+pub const MAX_TIMELINE_WIDTH: i32 = 1000;
+
+/// Parses a `--timeline-width` value: a number of characters from 1 to
+/// [`MAX_TIMELINE_WIDTH`].
+/// 
+/// # Arguments
+/// * `flag` - The option the value belongs to, used in the error message
+/// * `value` - The raw option value
+/// 
+/// # Returns
+/// * `Result<i32>` - The width, or an error naming the option if it is out of range
+fn parse_timeline_width(flag: &str, value: &str) -> Result<i32> {
+    match value.parse::<i32>() {
+        Ok(width) if (1..=MAX_TIMELINE_WIDTH).contains(&width) => Ok(width),
+        _ => Err(anyhow!(
+            "Invalid value for {}: {} (expected a width from 1 to {})",
+            flag,
+            value,
+            MAX_TIMELINE_WIDTH
+        )),
+    }
+}
+
+/// Parses a comma-separated list of column indices given as an option value.
+/// 
+/// # Arguments
+/// * `flag` - The option the value belongs to, used in the error message
+/// * `value` - The raw option value, e.g. "6,7,8"
+/// 
+/// # Returns
+/// * `Result<Vec<usize>>` - The parsed indices in the given order
+fn parse_index_list(flag: &str, value: &str) -> Result<Vec<usize>> {
+    value
+        .split(',')
+        .map(|index| parse_count(flag, index.trim()))
+        .collect()
+}
+
+/// Replaces each `@<file>` argument with the arguments listed in that file,
+/// one per line. Lines are trimmed, and blank lines and lines starting with
+/// `#` are skipped. Arguments read from a file are not expanded again.
+/// 
+/// # Arguments
+/// * `args` - The full argument list, including the program name
+/// 
+/// # Returns
+/// * `Result<Vec<String>>` - The argument list with response files expanded
+/// 
+/// # Errors
+/// Returns an error if a response file can't be read
+pub fn expand_response_files(args: &[String]) -> Result<Vec<String>> {
+    let mut expanded = Vec::with_capacity(args.len());
+
+    for (index, arg) in args.iter().enumerate() {
+        // The program name is never a response file
+        let path = match arg.strip_prefix('@') {
+            Some(path) if index > 0 && !path.is_empty() => path,
+            _ => {
+                expanded.push(arg.clone());
+                continue;
+            }
+        };
+
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read arguments from {}", path))?;
+        expanded.extend(
+            contents
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(String::from),
+        );
+    }
+
+    Ok(expanded)
+}
+
+/// Parses a comma-separated list of column names given as an option value.
+/// 
+/// # Arguments
+/// * `flag` - The option the value belongs to, used in the error message
+/// * `value` - The raw option value, e.g. "company,position,start,end"
+/// 
+/// # Returns
+/// * `Result<Vec<Field>>` - The named fields in the given order
+fn parse_column_list(flag: &str, value: &str) -> Result<Vec<Field>> {
+    value
+        .split(',')
+        .map(|name| {
+            Field::from_name(name).ok_or_else(|| anyhow!("Invalid value for {}: unknown column {}", flag, name.trim()))
+        })
+        .collect()
+}
+
+/// Parses a comma-separated list of date cells that mean "no date" given as
+/// an option value, skipping empty items.
+/// 
+/// # Arguments
+/// * `value` - The raw option value, e.g. "N/A,-,TBD"
+/// 
+/// # Returns
+/// * `Vec<String>` - The trimmed values in the given order
+fn parse_null_dates(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Parses command line arguments into the program options.
+/// 
+/// # Arguments
+/// * `args` - The full argument list, including the program name; `@<file>`
+///   arguments are expanded first (see [`expand_response_files`])
+/// 
+/// # Returns
+/// * `Result<Options>` - The parsed options
+/// 
+/// # Errors
+/// Returns an error if arguments are missing or invalid
+pub fn parse_args(args: &[String]) -> Result<Options> {
+    parse_expanded_args(&expand_response_files(args)?)
+}
+
+/// Parses command line arguments whose `@<file>` arguments have already been
+/// expanded with [`expand_response_files`], so the caller can reuse the
+/// expanded list (e.g. to find the `--error-format`).
+/// 
+/// # Arguments
+/// * `args` - The expanded argument list, including the program name
+/// 
+/// # Returns
+/// * `Result<Options>` - The parsed options
+/// 
+/// # Errors
+/// Returns an error if arguments are missing or invalid
+pub fn parse_expanded_args(args: &[String]) -> Result<Options> {
+    let program = args.first().map(String::as_str).unwrap_or("program");
+    let mut options = Options::default();
+    let mut positional = Vec::new();
+    // The argument that chose a non-default date mode, for conflict errors
+    let mut date_mode_flag = None;
+    let mut iter = args.iter().skip(1);
+
+    while let Some(arg) = iter.next() {
+        let mut value = |flag: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| anyhow!("Missing value for {}\n\n{}", flag, usage(program)))
+        };
+
+        match arg.as_str() {
+            "--since-company" => options.since_company = Some(value(arg)?),
+            "--verbose" => options.verbose = true,
+            "--strict" => options.strict = true,
+            "--preview" => options.preview = Some(parse_count(arg, &value(arg)?)?),
+            "--only-current" => options.only_current = true,
+            "--chronological-labels" => options.chronological_labels = true,
+            "--output-dir" => options.output_dir = Some(PathBuf::from(value(arg)?)),
+            "--split-per-entry" => options.split_per_entry = true,
+            "--input-glob" => options.input_glob = Some(value(arg)?),
+            "--hash" => options.hash = true,
+            "--no-blank-lines" => options.no_blank_lines = true,
+            "--fix-dates" => options.fix_dates = true,
+            "--present-label" => options.present_label = Some(value(arg)?),
+            "--include-salary" => options.include_salary = true,
+            "--error-format" => options.error_format = ErrorFormat::parse(&value(arg)?)?,
+            "--join-responsibilities" => {
+                options.responsibility_columns = parse_index_list(arg, &value(arg)?)?;
+            }
+            "--skill-experience" => options.skill_experience = true,
+            "--first-line-headers-only" => options.first_line_headers_only = true,
+            "--no-color" => options.no_color = true,
+            "--format" => {
+                let formats = value(arg)?
+                    .split(',')
+                    .map(|format| OutputFormat::parse(format.trim()))
+                    .collect::<Result<Vec<_>>>()?;
+                options.format = formats[0];
+                options.additional_formats = formats[1..].to_vec();
+            }
+            "--min-tenure" => options.min_tenure = Some(parse_count(arg, &value(arg)?)?),
+            "--tidy" => options.tidy = true,
+            "--company-sort-within-year" => options.company_sort_within_year = true,
+            "--diff" => options.diff = true,
+            "--force" => options.force = true,
+            "--cols-directive" => options.cols_directive = true,
+            "--ascii-only" => options.ascii_only = true,
+            "--separator" => options.separator = Some(value(arg)?),
+            "--count" => options.count = true,
+            "--undated" => options.undated = UndatedPlacement::parse(&value(arg)?)?,
+            "--explain" => options.explain = true,
+            "--sanity-check" => options.sanity_check = true,
+            "--pretty" => options.pretty = true,
+            "--title" => options.title = Some(value(arg)?),
+            "--subtitle" => options.subtitle = Some(value(arg)?),
+            "--stats" => options.stats = true,
+            "--terminator" => options.terminator = Some(parse_terminator(&value(arg)?)?),
+            "--quote-style" => options.quote_style = Some(parse_quote_style(&value(arg)?)?),
+            "--include-type" => options.include_type = true,
+            "--merge-adjacent" => options.merge_adjacent = true,
+            "--anonymize" => options.anonymize = true,
+            "--sort-by" => options.sort_by = SortBy::parse(&value(arg)?)?,
+            "--show-duration" => options.show_duration = true,
+            "--responsibilities-from-multiline" => options.multiline_bullets = true,
+            "--footer" => options.footer = true,
+            "--keep-order-stable" => options.keep_order_stable = true,
+            "--table-width" => options.table_width = Some(parse_count(arg, &value(arg)?)?),
+            "--max-gap" => options.max_gap = Some(parse_months(arg, &value(arg)?)?),
+            "--include-reference" => options.include_reference = true,
+            "--normalize-phone" => options.normalize_phone = true,
+            "--timeline-width" => options.timeline_width = Some(parse_timeline_width(arg, &value(arg)?)?),
+            "--create-dirs" => options.create_dirs = true,
+            "--emit-template" => options.emit_template = true,
+            "--include-zip" => options.include_zip = true,
+            "--self-check" => options.self_check = true,
+            "--icons" => options.icons = true,
+            "--responsibilities-sort" => options.responsibilities_sort = ResponsibilitiesSort::parse(&value(arg)?)?,
+            "--exclude-file" => options.exclude_file = Some(PathBuf::from(value(arg)?)),
+            "--normalize-punctuation" => options.normalize_punctuation = true,
+            "--require-columns" => {
+                options.required_columns = parse_column_list(arg, &value(arg)?)?;
+            }
+            "--toc" => options.toc = true,
+            "--round-dates-to-month" => options.round_dates_to_month = true,
+            "--date-format" => options.date_pattern = Some(parse_date_pattern(&value(arg)?)?),
+            "--post-command" => options.post_command = Some(parse_post_command(&value(arg)?)?),
+            "--null-dates" => options.null_dates = Some(parse_null_dates(&value(arg)?)),
+            "--quiet" => options.quiet = true,
+            "--numbered-responsibilities" => options.numbered_responsibilities = true,
+            "--min-entries" => options.min_entries = Some(parse_count(arg, &value(arg)?)?),
+            "--company-color" => options.company_colors = true,
+            "--flatten" => options.flatten = true,
+            "--include-keywords" => options.include_keywords = true,
+            "--sort-keywords" => options.sort_keywords = true,
+            "--warnings-file" => options.warnings_file = Some(PathBuf::from(value(arg)?)),
+            "--sheet" => options.sheet = Some(value(arg)?),
+            "--validate-emails" => options.validate_emails = true,
+            "--collapse-whitespace" => options.collapse_whitespace = true,
+            "--clipboard" => options.clipboard = true,
+            "--start-numbering-at" => options.start_numbering_at = Some(parse_count(arg, &value(arg)?)?),
+            "--output-bom" => options.output_bom = true,
+            "--aggregate-responsibilities" => options.aggregate_responsibilities = Some(AggregateResponsibilities::parse(&value(arg)?)?),
+            "--entry-filter" | "--filter" => options.entry_filter = Some(EntryFilter::parse(&value(arg)?)?),
+            "--strip-formula-prefix" => options.strip_formula_prefix = true,
+            "--position-sort-within-company" => options.position_sort_within_company = true,
+            "--relative-dates" => options.relative_dates = true,
+            "--date-mode" => {
+                let value = value(arg)?;
+                let mode = DateMode::parse(&value)?;
+                let flag = format!("{} {}", arg, value);
+                options.date_mode = options.date_mode.switch_to(mode, flag, &mut date_mode_flag)?;
+            }
+            "--day-first" => {
+                options.date_mode = options.date_mode.switch_to(DateMode::DayFirst, arg.clone(), &mut date_mode_flag)?;
+            }
+            "--output-template-file" => {
+                let path = value(arg)?;
+                let template = std::fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read output template: {}", path))?;
+                if !template.contains(ENTRIES_PLACEHOLDER) {
+                    return Err(anyhow!("The output template {} has no {} placeholder", path, ENTRIES_PLACEHOLDER));
+                }
+                options.output_template = Some(template);
+            }
+            flag if flag.starts_with("--") => {
+                return Err(anyhow!("Unknown option: {}\n\n{}", flag, usage(program)));
+            }
+            _ => positional.push(arg),
+        }
+    }
+
+    // With --emit-template the only positional argument is where to write it
+    if options.emit_template {
+        if positional.len() > 1 {
+            return Err(anyhow!(usage(program)));
+        }
+        options.template_path = positional.first().map(PathBuf::from);
+        return Ok(options);
+    }
+
+    // With --input-glob the only positional argument is the output path
+    if options.input_glob.is_none() {
+        match positional.first() {
+            Some(input) => options.input_path = PathBuf::from(input),
+            None => return Err(anyhow!(usage(program))),
+        }
+        positional.remove(0);
+    }
+    if positional.len() > 1 {
+        return Err(anyhow!(usage(program)));
+    }
+    if options.split_per_entry && options.formats().any(|format| format != OutputFormat::Text) {
+        return Err(anyhow!("--split-per-entry can only be used with the text format"));
+    }
+    if options.toc && options.formats().any(|format| format != OutputFormat::Text) {
+        return Err(anyhow!("--toc can only be used with the text format"));
+    }
+    if options.aggregate_responsibilities.is_some() && options.formats().any(|format| format != OutputFormat::Text) {
+        return Err(anyhow!("--aggregate-responsibilities can only be used with the text format"));
+    }
+    if options.relative_dates && options.formats().any(|format| format != OutputFormat::Text) {
+        return Err(anyhow!("--relative-dates can only be used with the text format"));
+    }
+    if options.numbered_responsibilities && options.formats().any(|format| format != OutputFormat::Text) {
+        return Err(anyhow!("--numbered-responsibilities can only be used with the text format"));
+    }
+    if options.start_numbering_at.is_some() && options.formats().any(|format| format != OutputFormat::Text) {
+        return Err(anyhow!("--start-numbering-at can only be used with the text format"));
+    }
+    if options.flatten && !options.formats().any(|format| format == OutputFormat::Csv) {
+        return Err(anyhow!("--flatten can only be used with --format csv"));
+    }
+    if options.quote_style.is_some() && !options.formats().any(|format| format == OutputFormat::Csv) {
+        return Err(anyhow!("--quote-style can only be used with --format csv"));
+    }
+    if options.table_width.is_some() && !options.formats().any(|format| format == OutputFormat::Table) {
+        return Err(anyhow!("--table-width can only be used with --format table"));
+    }
+    if options.sort_keywords && !options.include_keywords {
+        return Err(anyhow!("--sort-keywords can only be used with --include-keywords"));
+    }
+    if options.position_sort_within_company && !options.merge_adjacent {
+        return Err(anyhow!("--position-sort-within-company can only be used with --merge-adjacent"));
+    }
+    if options.responsibilities_sort != ResponsibilitiesSort::None
+        && !(options.multiline_bullets || options.numbered_responsibilities || options.aggregate_responsibilities.is_some()) {
+        return Err(anyhow!("--responsibilities-sort can only be used with --responsibilities-from-multiline, --numbered-responsibilities, or --aggregate-responsibilities"));
+    }
+    if options.force && !options.diff {
+        return Err(anyhow!("--force can only be used with --diff"));
+    }
+    if options.pretty && !options.formats().any(|format| format == OutputFormat::Json) {
+        return Err(anyhow!("--pretty can only be used with --format json"));
+    }
+    if options.preview.is_some() && !options.additional_formats.is_empty() {
+        return Err(anyhow!("--preview prints to stdout, so it can only be used with a single --format"));
+    }
+    if options.output_template.is_some() && !options.additional_formats.is_empty() {
+        return Err(anyhow!("--output-template-file wraps a single output, so it can only be used with a single --format"));
+    }
+    if options.output_template.is_some() && options.format == OutputFormat::Html {
+        return Err(anyhow!("--format html already writes a whole page, so it can't be used with --output-template-file"));
+    }
+    if options.clipboard && (options.split_per_entry || options.preview.is_some() || !options.additional_formats.is_empty()) {
+        return Err(anyhow!("--clipboard copies a single output, so it can't be used with --split-per-entry, --preview, or several formats"));
+    }
+    options.clipboard_only = options.clipboard && positional.is_empty() && options.output_dir.is_none();
+    if options.ascii_only {
+        let texts = [&mut options.title, &mut options.subtitle, &mut options.present_label, &mut options.separator];
+        for text in texts.into_iter().flatten() {
+            *text = transliterate(text);
+        }
+    }
+
+    options.output_path = match positional.first() {
+        Some(path) => PathBuf::from(path),
+        None => PathBuf::from("formatted_work_history.txt"),
+    };
+    if let Some(output_dir) = &options.output_dir {
+        options.output_path = output_dir.join(&options.output_path);
+    }
+
+    Ok(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::args;
+    use std::path::Path;
+
+    #[test]
+    fn parse_args_reads_paths_and_since_company() {
+        let options = parse_args(&args(&["in.csv", "--since-company", "Acme", "out.txt"])).unwrap();
+        assert_eq!(options.input_path, PathBuf::from("in.csv"));
+        assert_eq!(options.output_path, PathBuf::from("out.txt"));
+        assert_eq!(options.since_company.as_deref(), Some("Acme"));
+
+        let options = parse_args(&args(&["in.csv"])).unwrap();
+        assert_eq!(options.output_path, PathBuf::from("formatted_work_history.txt"));
+        assert!(options.since_company.is_none());
+        assert!(!options.verbose);
+        assert!(parse_args(&args(&["--verbose", "in.csv"])).unwrap().verbose);
+    }
+
+    #[test]
+    fn parse_args_rejects_bad_arguments() {
+        assert!(parse_args(&args(&[])).is_err());
+        assert!(parse_args(&args(&["a.csv", "b.txt", "c.txt"])).is_err());
+        assert!(parse_args(&args(&["in.csv", "--since-company"])).is_err());
+        assert!(parse_args(&args(&["in.csv", "--bogus"])).is_err());
+    }
+
+    #[test]
+    fn parse_args_reads_preview_count() {
+        assert_eq!(parse_args(&args(&["--preview", "3", "in.csv"])).unwrap().preview, Some(3));
+        assert!(parse_args(&args(&["--preview", "three", "in.csv"])).is_err());
+    }
+
+    #[test]
+    fn parse_index_list_rejects_non_numbers() {
+        assert_eq!(parse_index_list("--join-responsibilities", "6,7").unwrap(), [6, 7]);
+        assert!(parse_index_list("--join-responsibilities", "6,x").is_err());
+    }
+
+    #[test]
+    fn error_format_is_found_before_full_parsing() {
+        assert_eq!(ErrorFormat::from_args(&args(&["--error-format", "json", "--bogus"])), ErrorFormat::Json);
+        assert_eq!(ErrorFormat::from_args(&args(&["in.csv"])), ErrorFormat::Human);
+        assert!(parse_args(&args(&["--error-format", "xml", "in.csv"])).is_err());
+    }
+
+    #[test]
+    fn input_glob_takes_only_an_output_path() {
+        let options = parse_args(&args(&["--input-glob", "history_*.csv", "out.txt"])).unwrap();
+        assert_eq!(options.input_glob.as_deref(), Some("history_*.csv"));
+        assert_eq!(options.output_path, PathBuf::from("out.txt"));
+
+        let options = parse_args(&args(&["--input-glob", "history_*.csv"])).unwrap();
+        assert_eq!(options.output_path, PathBuf::from("formatted_work_history.txt"));
+
+        assert!(parse_args(&args(&["--input-glob", "history_*.csv", "in.csv", "out.txt"])).is_err());
+    }
+
+    #[test]
+    fn output_dir_is_joined_with_output_path() {
+        let options = parse_args(&args(&["--output-dir", "out", "in.csv"])).unwrap();
+        assert_eq!(options.output_path, Path::new("out").join("formatted_work_history.txt"));
+    }
+
+    #[test]
+    fn format_is_parsed_and_checked_against_split() {
+        assert_eq!(parse_args(&args(&["in.csv"])).unwrap().format, OutputFormat::Text);
+        let options = parse_args(&args(&["--format", "locations", "in.csv"])).unwrap();
+        assert_eq!(options.format, OutputFormat::Locations);
+        assert!(parse_args(&args(&["--format", "yaml", "in.csv"])).is_err());
+        assert!(parse_args(&args(&["--format", "locations", "--split-per-entry", "in.csv"])).is_err());
+    }
+
+    #[test]
+    fn pretty_requires_the_json_format() {
+        assert!(parse_args(&args(&["--format", "json", "--pretty", "in.csv"])).unwrap().pretty);
+        assert!(parse_args(&args(&["--pretty", "in.csv"])).is_err());
+        assert!(parse_args(&args(&["--format", "jsonl", "--pretty", "in.csv"])).is_err());
+    }
+
+    #[test]
+    fn terminator_accepts_names_and_single_characters() {
+        assert!(matches!(parse_terminator("CRLF").unwrap(), Terminator::CRLF));
+        assert!(matches!(parse_terminator("LF").unwrap(), Terminator::Any(b'\n')));
+        assert!(matches!(parse_terminator(";").unwrap(), Terminator::Any(b';')));
+        assert!(parse_terminator("").is_err());
+        assert!(parse_terminator(";;").is_err());
+        assert!(parse_terminator("§").is_err());
+    }
+
+    #[test]
+    fn response_files_are_expanded_in_place() {
+        let path = std::env::temp_dir().join(format!("work_history_args_{}.txt", std::process::id()));
+        std::fs::write(&path, "# Resume flags\n--since-company\nAcme Corp\n\n  --tidy  \n").unwrap();
+
+        let response_file = format!("@{}", path.display());
+        let options = parse_args(&args(&["--verbose", &response_file, "in.csv"])).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(options.verbose);
+        assert!(options.tidy);
+        assert_eq!(options.since_company.as_deref(), Some("Acme Corp"));
+        assert_eq!(options.input_path, PathBuf::from("in.csv"));
+    }
+
+    #[test]
+    fn missing_response_file_is_an_error() {
+        let err = parse_args(&args(&["@/nonexistent/args.txt", "in.csv"])).unwrap_err();
+        assert!(err.to_string().contains("/nonexistent/args.txt"));
+    }
+
+    #[test]
+    fn emit_template_takes_an_optional_path_instead_of_input() {
+        let options = parse_args(&args(&["--emit-template"])).unwrap();
+        assert!(options.emit_template);
+        assert_eq!(options.template_path, None);
+
+        let options = parse_args(&args(&["--emit-template", "template.csv"])).unwrap();
+        assert_eq!(options.template_path, Some(PathBuf::from("template.csv")));
+
+        assert!(parse_args(&args(&["--emit-template", "a.csv", "b.csv"])).is_err());
+    }
+
+    #[test]
+    fn format_accepts_a_comma_separated_list() {
+        let options = parse_args(&args(&["--format", "text, json", "in.csv"])).unwrap();
+        assert_eq!(options.formats().collect::<Vec<_>>(), [OutputFormat::Text, OutputFormat::Json]);
+        assert!(parse_args(&args(&["--format", "text,json", "--pretty", "in.csv"])).is_ok());
+
+        assert!(parse_args(&args(&["--format", "text,yaml", "in.csv"])).is_err());
+        let err = parse_args(&args(&["--format", "text,json", "--preview", "2", "in.csv"])).unwrap_err();
+        assert!(err.to_string().contains("--preview"));
+    }
+
+    #[test]
+    fn responsibilities_sort_values_are_parsed() {
+        let parsed = parse_args(&args(&["--responsibilities-sort", "length", "--responsibilities-from-multiline", "in.csv"]));
+        assert_eq!(parsed.unwrap().responsibilities_sort, ResponsibilitiesSort::Length);
+        assert_eq!(parse_args(&args(&["in.csv"])).unwrap().responsibilities_sort, ResponsibilitiesSort::None);
+        assert!(parse_args(&args(&["--responsibilities-sort", "random", "in.csv"])).is_err());
+    }
+
+    #[test]
+    fn require_columns_accepts_any_header_name() {
+        let options = parse_args(&args(&["--require-columns", "company, position,start,End Date", "in.csv"])).unwrap();
+        assert_eq!(options.required_columns, [Field::Company, Field::Position, Field::StartDate, Field::EndDate]);
+
+        let err = parse_args(&args(&["--require-columns", "company,shoe size", "in.csv"])).unwrap_err();
+        assert!(err.to_string().contains("unknown column shoe size"));
+    }
+
+    #[test]
+    fn date_format_accepts_year_and_chrono_patterns() {
+        let options = parse_args(&args(&["--date-format", "year", "in.csv"])).unwrap();
+        assert_eq!(options.date_format().pattern, "%Y");
+        let options = parse_args(&args(&["--date-format", "%b %Y", "in.csv"])).unwrap();
+        assert_eq!(options.date_format().pattern, "%b %Y");
+        assert_eq!(Options::default().date_format(), DateFormat::default());
+        assert!(parse_args(&args(&["--date-format", "%Q", "in.csv"])).is_err());
+    }
+
+    #[test]
+    fn post_command_must_name_a_program() {
+        let options = parse_args(&args(&["--post-command", " pandoc {output} -o out.pdf ", "in.csv"])).unwrap();
+        assert_eq!(options.post_command.as_deref(), Some("pandoc {output} -o out.pdf"));
+        assert!(parse_args(&args(&["--post-command", "  ", "in.csv"])).is_err());
+        assert!(parse_args(&args(&["--post-command", "{output}", "in.csv"])).is_err());
+    }
+
+    #[test]
+    fn toc_needs_the_text_format() {
+        assert!(parse_args(&args(&["--toc", "in.csv"])).unwrap().toc);
+        let err = parse_args(&args(&["--toc", "--format", "html", "in.csv"])).unwrap_err();
+        assert_eq!(err.to_string(), "--toc can only be used with the text format");
+        assert!(parse_args(&args(&["--toc", "--format", "text,json", "in.csv"])).is_err());
+    }
+
+    #[test]
+    fn timeline_width_must_be_in_range() {
+        assert_eq!(parse_args(&args(&["--timeline-width", "80", "in.csv"])).unwrap().timeline_width, Some(80));
+        let err = parse_args(&args(&["--timeline-width", "4294967296", "in.csv"])).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid value for --timeline-width: 4294967296 (expected a width from 1 to 1000)");
+        assert!(parse_args(&args(&["--timeline-width", "0", "in.csv"])).is_err());
+        assert!(parse_args(&args(&["--timeline-width", "1001", "in.csv"])).is_err());
+    }
+
+    #[test]
+    fn aggregate_responsibilities_needs_the_text_format() {
+        assert!(parse_args(&args(&["--aggregate-responsibilities", "only", "in.csv"])).is_ok());
+        let err = parse_args(&args(&["--aggregate-responsibilities", "only", "--format", "json", "in.csv"])).unwrap_err();
+        assert_eq!(err.to_string(), "--aggregate-responsibilities can only be used with the text format");
+    }
+
+    #[test]
+    fn relative_dates_needs_the_text_format() {
+        assert!(parse_args(&args(&["--relative-dates", "in.csv"])).unwrap().relative_dates);
+        let err = parse_args(&args(&["--relative-dates", "--format", "csv", "in.csv"])).unwrap_err();
+        assert_eq!(err.to_string(), "--relative-dates can only be used with the text format");
+    }
+
+    #[test]
+    fn numbered_responsibilities_needs_the_text_format() {
+        assert!(parse_args(&args(&["--numbered-responsibilities", "in.csv"])).unwrap().numbered_responsibilities);
+        let err = parse_args(&args(&["--numbered-responsibilities", "--format", "json", "in.csv"])).unwrap_err();
+        assert_eq!(err.to_string(), "--numbered-responsibilities can only be used with the text format");
+    }
+
+    #[test]
+    fn start_numbering_at_needs_the_text_format() {
+        assert_eq!(parse_args(&args(&["--start-numbering-at", "5", "in.csv"])).unwrap().start_numbering_at, Some(5));
+        let err = parse_args(&args(&["--start-numbering-at", "5", "--format", "html", "in.csv"])).unwrap_err();
+        assert_eq!(err.to_string(), "--start-numbering-at can only be used with the text format");
+    }
+
+    #[test]
+    fn flatten_needs_the_csv_format() {
+        assert!(parse_args(&args(&["--flatten", "--format", "csv", "in.csv"])).unwrap().flatten);
+        let err = parse_args(&args(&["--flatten", "in.csv"])).unwrap_err();
+        assert_eq!(err.to_string(), "--flatten can only be used with --format csv");
+    }
+
+    #[test]
+    fn quote_style_needs_the_csv_format() {
+        assert!(parse_args(&args(&["--quote-style", "always", "--format", "csv", "in.csv"])).unwrap().quote_style.is_some());
+        let err = parse_args(&args(&["--quote-style", "always", "in.csv"])).unwrap_err();
+        assert_eq!(err.to_string(), "--quote-style can only be used with --format csv");
+    }
+
+    #[test]
+    fn table_width_needs_the_table_format() {
+        assert_eq!(parse_args(&args(&["--table-width", "80", "--format", "table", "in.csv"])).unwrap().table_width, Some(80));
+        let err = parse_args(&args(&["--table-width", "80", "in.csv"])).unwrap_err();
+        assert_eq!(err.to_string(), "--table-width can only be used with --format table");
+    }
+
+    #[test]
+    fn sort_keywords_needs_include_keywords() {
+        assert!(parse_args(&args(&["--sort-keywords", "--include-keywords", "in.csv"])).unwrap().sort_keywords);
+        let err = parse_args(&args(&["--sort-keywords", "in.csv"])).unwrap_err();
+        assert_eq!(err.to_string(), "--sort-keywords can only be used with --include-keywords");
+    }
+
+    #[test]
+    fn position_sort_within_company_needs_merge_adjacent() {
+        let options = parse_args(&args(&["--position-sort-within-company", "--merge-adjacent", "in.csv"])).unwrap();
+        assert!(options.position_sort_within_company);
+        let err = parse_args(&args(&["--position-sort-within-company", "in.csv"])).unwrap_err();
+        assert_eq!(err.to_string(), "--position-sort-within-company can only be used with --merge-adjacent");
+    }
+
+    #[test]
+    fn responsibilities_sort_needs_a_list_of_responsibilities() {
+        let sorted = |extra: &str| parse_args(&args(&["--responsibilities-sort", "alpha", extra, "in.csv"]));
+        assert!(sorted("--responsibilities-from-multiline").is_ok());
+        assert!(sorted("--numbered-responsibilities").is_ok());
+        let err = sorted("--tidy").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "--responsibilities-sort can only be used with --responsibilities-from-multiline, --numbered-responsibilities, or --aggregate-responsibilities"
+        );
+        assert!(parse_args(&args(&["--responsibilities-sort", "none", "in.csv"])).is_ok());
+    }
+
+    #[test]
+    fn force_needs_diff() {
+        assert!(parse_args(&args(&["--diff", "--force", "in.csv"])).unwrap().force);
+        let err = parse_args(&args(&["--force", "in.csv"])).unwrap_err();
+        assert_eq!(err.to_string(), "--force can only be used with --diff");
+    }
+
+    #[test]
+    fn max_gap_must_fit_in_months() {
+        assert_eq!(parse_args(&args(&["--max-gap", "6", "in.csv"])).unwrap().max_gap, Some(6));
+        let err = parse_args(&args(&["--max-gap", "2147483648", "in.csv"])).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid value for --max-gap: 2147483648 (expected a whole number)");
+        assert!(parse_args(&args(&["--max-gap", "-1", "in.csv"])).is_err());
+    }
+
+    #[test]
+    fn null_dates_replace_the_default_sentinels() {
+        assert_eq!(Options::default().null_dates(), DEFAULT_NULL_DATES);
+        let options = parse_args(&args(&["--null-dates", "N/A, -,,TBD", "in.csv"])).unwrap();
+        assert_eq!(options.null_dates(), ["N/A", "-", "TBD"]);
+    }
+
+    #[test]
+    fn clipboard_skips_the_file_without_an_output_path() {
+        let options = parse_args(&args(&["--clipboard", "in.csv"])).unwrap();
+        assert!(options.clipboard && options.clipboard_only);
+        let options = parse_args(&args(&["--clipboard", "in.csv", "out.txt"])).unwrap();
+        assert!(options.clipboard && !options.clipboard_only);
+        assert!(parse_args(&args(&["--clipboard", "--split-per-entry", "in.csv"])).is_err());
+        assert!(parse_args(&args(&["--clipboard", "--format", "text,json", "in.csv"])).is_err());
+    }
+
+    #[test]
+    fn output_template_is_read_once_and_needs_a_single_non_html_format() {
+        let path = std::env::temp_dir().join(format!("work_history_options_template_{}.tex", std::process::id()));
+        std::fs::write(&path, "\\begin{document}\n{{entries}}\\end{document}\n").unwrap();
+        let template = path.to_str().unwrap();
+        let options = parse_args(&args(&["--output-template-file", template, "in.csv"])).unwrap();
+        let html = parse_args(&args(&["--output-template-file", template, "--format", "html", "in.csv"]));
+        let several = parse_args(&args(&["--output-template-file", template, "--format", "text,json", "in.csv"]));
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(options.output_template.as_deref(), Some("\\begin{document}\n{{entries}}\\end{document}\n"));
+        assert!(html.is_err());
+        assert!(several.is_err());
+        assert!(parse_args(&args(&["--output-template-file", template, "in.csv"])).is_err());
+    }
+
+    #[test]
+    fn output_template_without_entries_placeholder_is_rejected() {
+        let path = std::env::temp_dir().join(format!("work_history_options_no_placeholder_{}.tex", std::process::id()));
+        std::fs::write(&path, "\\begin{document}\\end{document}\n").unwrap();
+        let result = parse_args(&args(&["--output-template-file", path.to_str().unwrap(), "in.csv"]));
+        std::fs::remove_file(&path).unwrap();
+
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("has no {{entries}} placeholder"));
+    }
+
+    #[test]
+    fn aggregate_responsibilities_mode_is_parsed() {
+        let options = parse_args(&args(&["--aggregate-responsibilities", "only", "in.csv"])).unwrap();
+        assert_eq!(options.aggregate_responsibilities, Some(AggregateResponsibilities::Only));
+        assert_eq!(parse_args(&args(&["in.csv"])).unwrap().aggregate_responsibilities, None);
+        assert!(parse_args(&args(&["--aggregate-responsibilities", "all", "in.csv"])).is_err());
+    }
+
+    #[test]
+    fn entry_filter_is_parsed_when_the_arguments_are() {
+        let options = parse_args(&args(&["--filter", "duration > 1y", "in.csv"])).unwrap();
+        assert_eq!(options.entry_filter, Some(EntryFilter::parse("duration > 12").unwrap()));
+        let err = parse_args(&args(&["--entry-filter", "company", "in.csv"])).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid --entry-filter: expected an operator at the end of the filter");
+    }
+
+    #[test]
+    fn date_mode_values_are_parsed() {
+        let options = parse_args(&args(&["--date-mode", "iso-strict", "in.csv"])).unwrap();
+        assert_eq!(options.date_mode, DateMode::IsoStrict);
+        assert_eq!(parse_args(&args(&["in.csv"])).unwrap().date_mode, DateMode::Lenient);
+        assert!(parse_args(&args(&["--date-mode", "iso", "in.csv"])).is_err());
+    }
+
+    #[test]
+    fn day_first_conflicts_with_iso_strict() {
+        assert_eq!(parse_args(&args(&["--day-first", "in.csv"])).unwrap().date_mode, DateMode::DayFirst);
+        let options = parse_args(&args(&["--date-mode", "day-first", "--day-first", "in.csv"])).unwrap();
+        assert_eq!(options.date_mode, DateMode::DayFirst);
+        let err = parse_args(&args(&["--day-first", "--date-mode", "iso-strict", "in.csv"])).unwrap_err();
+        assert_eq!(err.to_string(), "--day-first conflicts with --date-mode iso-strict; use only one");
+        let err = parse_args(&args(&["--date-mode", "day-first", "--date-mode", "iso-strict", "in.csv"])).unwrap_err();
+        assert_eq!(err.to_string(), "--date-mode day-first conflicts with --date-mode iso-strict; use only one");
+        assert!(parse_args(&args(&["--date-mode", "iso-strict", "--day-first", "in.csv"])).is_err());
+    }
+}
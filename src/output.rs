@@ -0,0 +1,2461 @@
+//! Writers for the formatted work history output.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::NaiveDate;
+use deunicode::deunicode_char;
+use sha2::{Digest, Sha256};
+use similar::TextDiff;
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::columns::Field;
+use crate::history::{format_date, format_end_date, month_number, DateFormat, WorkHistory, PRESENT_LABEL};
+use crate::options::{AggregateResponsibilities, Options, OutputFormat, ResponsibilitiesSort};
+use crate::skills::format_months;
+
+/// Builds the heading line for an entry.
+/// 
+/// Entries are normally numbered in output order ("Work History 1"), from
+/// `start_numbering_at` if it is set. With chronological labels the entries,
+/// written newest first, are instead numbered by their position in time
+/// ("Job #6 of 6 (most recent)").
+/// 
+/// # Arguments
+/// * `index` - Zero-based position of the entry in the output
+/// * `total` - Total number of entries
+/// * `options` - Output options
+/// 
+/// # Returns
+/// * `String` - The heading line
+fn entry_heading(index: usize, total: usize, options: &Options) -> String {
+    if !options.chronological_labels {
+        return format!("Work History {}", index + options.start_numbering_at.unwrap_or(1));
+    }
+
+    let position = total - index;
+    if position == total {
+        format!("Job #{} of {} (most recent)", position, total)
+    } else {
+        format!("Job #{} of {}", position, total)
+    }
+}
+
+/// Writes work history entries in the plain text format.
+/// 
+/// # Arguments
+/// * `output` - Destination for the formatted text
+/// * `work_histories` - The entries to write, in output order
+/// * `options` - Output options; `preview` limits how many entries are written,
+///   `separator` replaces the blank line between entries,
+///   `aggregate_responsibilities` and `toc` add a "Key Responsibilities"
+///   section and a table of contents before the first entry, and `footer`
+///   adds a line after the last entry
+/// 
+/// # Returns
+/// * `Result<()>` - Ok if writing succeeds, Error otherwise
+pub fn write_text<W: Write>(output: &mut W, work_histories: &[WorkHistory], options: &Options) -> Result<()> {
+    let total = work_histories.len();
+    let shown = options.preview.unwrap_or(total);
+
+    write_title_block(output, options)?;
+    if options.aggregate_responsibilities.is_some() {
+        write_key_responsibilities(output, &work_histories[..shown.min(total)], options)?;
+    }
+    if options.toc {
+        write_table_of_contents(output, &work_histories[..shown.min(total)], total, options)?;
+    }
+    for (index, history) in work_histories.iter().enumerate().take(shown) {
+        if index > 0 {
+            if let Some(separator) = &options.separator {
+                writeln!(output, "{}", separator)?;
+            }
+        }
+        write_text_entry(output, index, total, history, options)?;
+    }
+    if options.footer {
+        writeln!(output, "{}", footer_line(options))?;
+    }
+
+    Ok(())
+}
+
+/// Writes the `--toc` table of contents: a "Contents" line, then one line
+/// per entry naming its heading (as written above the entry), company, and
+/// dates, e.g. "- Work History 1: Acme Corp — 01/2020 – Present", and a
+/// blank line.
+/// 
+/// # Arguments
+/// * `output` - Destination for the formatted text
+/// * `work_histories` - The entries that will be written, in output order
+/// * `total` - Total number of entries, which the headings are numbered out of
+/// * `options` - Output options, for the headings and date format
+/// 
+/// # Returns
+/// * `Result<()>` - Ok if writing succeeds, Error otherwise
+fn write_table_of_contents<W: Write>(
+    output: &mut W,
+    work_histories: &[WorkHistory],
+    total: usize,
+    options: &Options,
+) -> Result<()> {
+    let fmt = options.date_format();
+
+    writeln!(output, "Contents")?;
+    for (index, history) in work_histories.iter().enumerate() {
+        let heading = entry_heading(index, total, options);
+        match history.date_range_string(&fmt) {
+            dates if dates.is_empty() => writeln!(output, "- {}: {}", heading, history.display_company())?,
+            dates => {
+                let dash = if options.ascii_only { "--" } else { "—" };
+                writeln!(output, "- {}: {} {} {}", heading, history.display_company(), dash, dates)?
+            }
+        }
+    }
+    writeln!(output)?;
+
+    Ok(())
+}
+
+/// Writes the `--aggregate-responsibilities` section: a "Key
+/// Responsibilities" line, then every entry's responsibilities split at
+/// semicolons and line breaks as "- " bullets, and a blank line.
+/// 
+/// Items that differ only in case or a trailing period are written once, as
+/// first seen. They're sorted alphabetically, ignoring case, or shortest
+/// first with `--responsibilities-sort length`. `tidy` applies to each item.
+/// 
+/// # Arguments
+/// * `output` - Destination for the formatted text
+/// * `work_histories` - The entries that will be written
+/// * `options` - Output options
+/// 
+/// # Returns
+/// * `Result<()>` - Ok if writing succeeds, Error otherwise
+fn write_key_responsibilities<W: Write>(output: &mut W, work_histories: &[WorkHistory], options: &Options) -> Result<()> {
+    let mut seen = HashSet::new();
+    let mut items: Vec<&str> = work_histories
+        .iter()
+        .flat_map(|history| responsibility_lines(&history.responsibilities))
+        .flat_map(|line| line.split(';'))
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .filter(|item| seen.insert(item.trim_end_matches('.').to_lowercase()))
+        .collect();
+    match options.responsibilities_sort {
+        ResponsibilitiesSort::Length => items.sort_by_key(|item| item.chars().count()),
+        ResponsibilitiesSort::None | ResponsibilitiesSort::Alpha => items.sort_by_cached_key(|item| item.to_lowercase()),
+    }
+
+    writeln!(output, "Key Responsibilities")?;
+    for item in items {
+        let item = if options.tidy { tidy_sentence(item) } else { item.to_string() };
+        writeln!(output, "- {}", item)?;
+    }
+    writeln!(output)?;
+
+    Ok(())
+}
+
+/// Builds the `--footer` line, e.g. "Generated by csv_to_work_history_parser
+/// v1.0.0 on 2025-09-12 from input.csv".
+/// 
+/// # Arguments
+/// * `options` - Output options, for the input name and today's date
+/// 
+/// # Returns
+/// * `String` - The footer line
+fn footer_line(options: &Options) -> String {
+    let input = match &options.input_glob {
+        Some(pattern) => pattern.clone(),
+        None => options
+            .input_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| options.input_path.display().to_string()),
+    };
+    format!(
+        "Generated by {} v{} on {} from {}",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION"),
+        options.today().format("%Y-%m-%d"),
+        input
+    )
+}
+
+/// Writes the distinct locations of the entries, sorted, one per line.
+/// 
+/// # Arguments
+/// * `output` - Destination for the list
+/// * `work_histories` - The entries whose locations are listed
+/// 
+/// # Returns
+/// * `Result<()>` - Ok if writing succeeds, Error otherwise
+pub fn write_locations<W: Write>(output: &mut W, work_histories: &[WorkHistory]) -> Result<()> {
+    let locations: BTreeSet<&str> = work_histories
+        .iter()
+        .map(|history| history.location.as_str())
+        .filter(|location| !location.is_empty())
+        .collect();
+
+    for location in locations {
+        writeln!(output, "{}", location)?;
+    }
+
+    Ok(())
+}
+
+/// Writes all entries as one JSON array, followed by a newline.
+/// 
+/// # Arguments
+/// * `output` - Destination for the JSON
+/// * `work_histories` - The entries to write, in output order
+/// * `pretty` - Indent the JSON instead of writing it on one line
+/// 
+/// # Returns
+/// * `Result<()>` - Ok if writing succeeds, Error otherwise
+pub fn write_json<W: Write>(output: &mut W, work_histories: &[WorkHistory], pretty: bool) -> Result<()> {
+    if pretty {
+        serde_json::to_writer_pretty(&mut *output, work_histories)?;
+    } else {
+        serde_json::to_writer(&mut *output, work_histories)?;
+    }
+    writeln!(output)?;
+
+    Ok(())
+}
+
+/// Writes each entry as a JSON object on its own line (JSON Lines).
+/// 
+/// # Arguments
+/// * `output` - Destination for the JSON lines
+/// * `work_histories` - The entries to write, in output order
+/// 
+/// # Returns
+/// * `Result<()>` - Ok if writing succeeds, Error otherwise
+pub fn write_json_lines<W: Write>(output: &mut W, work_histories: &[WorkHistory]) -> Result<()> {
+    for history in work_histories {
+        serde_json::to_writer(&mut *output, history)?;
+        writeln!(output)?;
+    }
+
+    Ok(())
+}
+
+/// Writes each entry as a paragraph of prose, separated by blank lines.
+/// 
+/// # Arguments
+/// * `output` - Destination for the paragraphs
+/// * `work_histories` - The entries to write, in output order
+/// * `options` - Output options
+/// 
+/// # Returns
+/// * `Result<()>` - Ok if writing succeeds, Error otherwise
+pub fn write_prose<W: Write>(output: &mut W, work_histories: &[WorkHistory], options: &Options) -> Result<()> {
+    for (index, history) in work_histories.iter().enumerate() {
+        if index > 0 {
+            writeln!(output)?;
+        }
+        writeln!(output, "{}", prose_paragraph(history, &options.date_format()))?;
+    }
+
+    Ok(())
+}
+
+/// Describes an entry in a sentence, e.g. "From 01/2020 to 03/2022, I worked
+/// as a Software Engineer at Acme Corp in Springfield, IL, where I built the
+/// billing system."
+/// 
+/// Ongoing jobs are written as "Since 01/2020, I have worked as ...", and
+/// the location and responsibilities clauses are left out when empty.
+/// 
+/// # Arguments
+/// * `history` - The entry to describe
+/// * `fmt` - How the dates are rendered
+/// 
+/// # Returns
+/// * `String` - The paragraph, ending with a period
+fn prose_paragraph(history: &WorkHistory, fmt: &DateFormat) -> String {
+    let mut sentence = match (history.start_date, history.end_date) {
+        (Some(start), Some(end)) => {
+            format!("From {} to {}, I worked", fmt.format(start), fmt.format(end))
+        }
+        (Some(start), None) => format!("Since {}, I have worked", fmt.format(start)),
+        (None, _) => "I worked".to_string(),
+    };
+
+    let position = history.position.trim();
+    if !position.is_empty() {
+        sentence.push_str(&format!(" as {} {}", indefinite_article(position), position));
+    }
+    sentence.push_str(&format!(" at {}", history.display_company().trim()));
+    if !history.location.is_empty() {
+        sentence.push_str(&format!(" in {}", history.location));
+    }
+
+    let responsibilities = responsibility_lines(&history.responsibilities).join(" ");
+    let responsibilities = responsibilities.trim_end_matches(['.', ';', ',']);
+    if !responsibilities.is_empty() {
+        sentence.push_str(&format!(", where I {}", lowercase_first(responsibilities)));
+    }
+    sentence.push('.');
+    sentence
+}
+
+/// Picks "a" or "an" for a noun phrase by its first letter.
+fn indefinite_article(phrase: &str) -> &'static str {
+    match phrase.chars().next() {
+        Some(c) if "aeiouAEIOU".contains(c) => "an",
+        _ => "a",
+    }
+}
+
+/// Lowercases the first letter of a clause so it reads mid-sentence,
+/// leaving acronyms such as "AWS" alone.
+fn lowercase_first(clause: &str) -> String {
+    let mut chars = clause.chars();
+    match (chars.next(), chars.next()) {
+        (Some(first), second) if !second.is_some_and(char::is_uppercase) => {
+            first.to_lowercase().chain(clause[first.len_utf8()..].chars()).collect()
+        }
+        _ => clause.to_string(),
+    }
+}
+
+/// Column headings of `--format table`.
+const TABLE_HEADER: [&str; 4] = ["Company", "Position", "Dates", "Location"];
+
+/// Narrowest a table column is shrunk to when fitting `--table-width`.
+const MIN_TABLE_COLUMN: usize = 5;
+
+/// Writes the entries as an aligned ASCII table with a header row, one row
+/// per entry. Column widths fit the widest value; with `--table-width` the
+/// widest columns are narrowed (down to [`MIN_TABLE_COLUMN`]) until the
+/// table fits, and values that no longer fit end in "...".
+/// 
+/// # Arguments
+/// * `output` - Destination for the table
+/// * `work_histories` - The entries to write, in output order
+/// * `options` - Output options, holding the table width
+/// 
+/// # Returns
+/// * `Result<()>` - Ok if writing succeeds, Error otherwise
+pub fn write_table<W: Write>(output: &mut W, work_histories: &[WorkHistory], options: &Options) -> Result<()> {
+    let fmt = options.date_format();
+    let rows: Vec<[String; 4]> = work_histories
+        .iter()
+        .map(|history| {
+            [
+                history.display_company().to_string(),
+                history.position.clone(),
+                history.date_range_string(&fmt),
+                history.location.clone(),
+            ]
+        })
+        .collect();
+
+    // Size each column to its widest cell, then shrink the widest to fit
+    let mut widths = TABLE_HEADER.map(|heading| heading.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    if let Some(table_width) = options.table_width {
+        // Each column adds "| " before and " " after, plus the closing "|"
+        let borders = 3 * widths.len() + 1;
+        while widths.iter().sum::<usize>() + borders > table_width {
+            let widest = (0..widths.len()).max_by_key(|&index| widths[index]).unwrap_or_default();
+            if widths[widest] <= MIN_TABLE_COLUMN {
+                break;
+            }
+            widths[widest] -= 1;
+        }
+    }
+
+    let rule = widths.iter().map(|width| format!("+{}", "-".repeat(width + 2))).collect::<String>() + "+";
+    let write_row = |output: &mut W, cells: &[&str]| -> Result<()> {
+        for (cell, width) in cells.iter().zip(widths) {
+            write!(output, "| {} ", pad_cell(cell, width))?;
+        }
+        writeln!(output, "|")?;
+        Ok(())
+    };
+
+    writeln!(output, "{}", rule)?;
+    write_row(output, &TABLE_HEADER)?;
+    writeln!(output, "{}", rule)?;
+    for row in &rows {
+        write_row(output, &row.each_ref().map(String::as_str))?;
+    }
+    writeln!(output, "{}", rule)?;
+
+    Ok(())
+}
+
+/// Pads a table cell to exactly `width` characters, truncating it with "..."
+/// when it is longer.
+fn pad_cell(cell: &str, width: usize) -> String {
+    let length = cell.chars().count();
+    if length <= width {
+        return format!("{}{}", cell, " ".repeat(width - length));
+    }
+    let kept: String = cell.chars().take(width.saturating_sub(3)).collect();
+    format!("{}...", kept.trim_end())
+        .chars()
+        .chain(std::iter::repeat(' '))
+        .take(width)
+        .collect()
+}
+
+/// Width of the bars of `--format timeline` without `--timeline-width`.
+pub const DEFAULT_TIMELINE_WIDTH: i32 = 60;
+
+/// Writes the entries as an ASCII timeline: a row per entry (so overlapping
+/// jobs get separate rows) with a `#` bar spanning its months, under an axis
+/// labeling the start of each year that fits.
+/// 
+/// The axis runs from the earliest start date to the latest end date, with
+/// ongoing jobs running until today, scaled to `--timeline-width` characters.
+/// Undated entries are left out.
+/// 
+/// # Arguments
+/// * `output` - Destination for the timeline
+/// * `work_histories` - The entries to write, in output order
+/// * `options` - Output options, holding the timeline width
+/// 
+/// # Returns
+/// * `Result<()>` - Ok if writing succeeds, Error otherwise
+pub fn write_timeline<W: Write>(output: &mut W, work_histories: &[WorkHistory], options: &Options) -> Result<()> {
+    let today = options.today();
+    let spans: Vec<(&WorkHistory, i32, i32)> = work_histories
+        .iter()
+        .filter_map(|history| history.month_span(today).map(|(start, end)| (history, start, end.max(start + 1))))
+        .collect();
+    let (Some(first), Some(last)) = (
+        spans.iter().map(|(_, start, _)| *start).min(),
+        spans.iter().map(|(_, _, end)| *end).max(),
+    ) else {
+        return Ok(());
+    };
+
+    let width = options.timeline_width.unwrap_or(DEFAULT_TIMELINE_WIDTH);
+    let total = last - first;
+    // First month (relative to `first`) shown in each column
+    let column_start = |column: i32| column * total / width;
+    let label_width = spans.iter().map(|(history, _, _)| history.display_company().chars().count()).max().unwrap_or(0);
+
+    // Label each January that falls on a column, leaving a space between labels
+    let mut axis = String::new();
+    for year in (first / 12)..=(last / 12) {
+        let month = year * 12 - first;
+        let Some(column) = (0..width).find(|&column| column_start(column) >= month) else {
+            break;
+        };
+        let label = year.to_string();
+        let gap = if axis.is_empty() { 0 } else { 1 };
+        if column as usize >= axis.len() + gap && column as usize + label.len() <= width as usize {
+            axis.push_str(&" ".repeat(column as usize - axis.len()));
+            axis.push_str(&label);
+        }
+    }
+    writeln!(output, "{}  {}", " ".repeat(label_width), axis)?;
+
+    for (history, start, end) in spans {
+        let bar: String = (0..width)
+            .map(|column| {
+                let covered = first + column_start(column) < end && first + column_start(column + 1) > start;
+                if covered { '#' } else { ' ' }
+            })
+            .collect();
+        let company = history.display_company();
+        writeln!(output, "{}{} |{}|", company, " ".repeat(label_width - company.chars().count()), bar)?;
+    }
+
+    Ok(())
+}
+
+/// Header row of `--format csv`, using names the reader recognizes.
+const CSV_HEADER: [&str; 11] = [
+    "Company", "Display Name", "Job Title", "Department", "Start Date", "End Date",
+    "Address", "Description", "Salary", "Skills", "Type",
+];
+
+/// Writes the entries as CSV in the input layout, so the file can be read
+/// back (or edited and re-run) as the same entries.
+/// 
+/// With `flatten`, cells spanning several lines (usually multiline
+/// responsibilities) are joined into one line with " / ", so every record
+/// is a single line for spreadsheet imports.
+/// 
+/// # Arguments
+/// * `output` - Destination for the CSV
+/// * `work_histories` - The entries to write, in output order
+/// * `options` - Output options; `quote_style` controls quoting
+/// 
+/// # Returns
+/// * `Result<()>` - Ok if writing succeeds, Error otherwise
+pub fn write_csv<W: Write>(output: &mut W, work_histories: &[WorkHistory], options: &Options) -> Result<()> {
+    let mut writer = csv::WriterBuilder::new()
+        .quote_style(options.quote_style.unwrap_or(csv::QuoteStyle::Necessary))
+        .from_writer(output);
+
+    writer.write_record(CSV_HEADER)?;
+    for history in work_histories {
+        let start_date = history.start_date.map(|date| date.format("%m/%d/%Y").to_string());
+        let end_date = match (history.start_date, history.end_date) {
+            (_, Some(date)) => date.format("%m/%d/%Y").to_string(),
+            (Some(_), None) => PRESENT_LABEL.to_string(),
+            (None, None) => String::new(),
+        };
+        let record = [
+            history.company.as_str(),
+            history.display_name.as_deref().unwrap_or(""),
+            &history.position,
+            history.department.as_deref().unwrap_or(""),
+            start_date.as_deref().unwrap_or(""),
+            &end_date,
+            &history.location,
+            &history.responsibilities,
+            history.salary.as_deref().unwrap_or(""),
+            &history.skills.join(", "),
+            history.employment_type.as_deref().unwrap_or(""),
+        ];
+        if options.flatten {
+            writer.write_record(record.map(|cell| responsibility_lines(cell).join(" / ")))?;
+        } else {
+            writer.write_record(record)?;
+        }
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Writes the entries as an iCalendar file with one all-day event per job,
+/// from its start date through its end date (today for an ongoing job).
+/// 
+/// The summary is "<position> at <company>", with the location and
+/// responsibilities set when present. Text values are escaped, lines end in
+/// CRLF, and long lines are folded as RFC 5545 requires. Undated entries are
+/// left out.
+/// 
+/// # Arguments
+/// * `output` - Destination for the calendar
+/// * `work_histories` - The entries to write, in output order
+/// * `options` - Output options, for today's date
+/// 
+/// # Returns
+/// * `Result<()>` - Ok if writing succeeds, Error otherwise
+pub fn write_ics<W: Write>(output: &mut W, work_histories: &[WorkHistory], options: &Options) -> Result<()> {
+    let today = options.today();
+    let stamp = today.format("%Y%m%dT000000Z").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//csv_to_work_history_parser//Work History//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+
+    for (index, history) in work_histories.iter().enumerate() {
+        let Some(start_date) = history.start_date else {
+            continue;
+        };
+        // All-day events end on the (exclusive) day after the last one
+        let last_day = history.end_date.unwrap_or(today).max(start_date);
+        let end_date = last_day.succ_opt().unwrap_or(last_day);
+
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}-{}@work-history", index + 1, start_date.format("%Y%m%d")));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("DTSTART;VALUE=DATE:{}", start_date.format("%Y%m%d")));
+        lines.push(format!("DTEND;VALUE=DATE:{}", end_date.format("%Y%m%d")));
+        lines.push(format!(
+            "SUMMARY:{}",
+            ics_escape(&format!("{} at {}", history.position, history.display_company()))
+        ));
+        if !history.location.is_empty() {
+            lines.push(format!("LOCATION:{}", ics_escape(&history.location)));
+        }
+        if !history.responsibilities.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", ics_escape(&history.responsibilities)));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    for line in lines {
+        write!(output, "{}\r\n", fold_ics_line(&line))?;
+    }
+
+    Ok(())
+}
+
+/// Escapes an iCalendar text value: backslashes, semicolons, and commas are
+/// backslash-escaped and line breaks become `\n`.
+fn ics_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.replace("\r\n", "\n").chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' | '\r' => escaped.push_str("\\n"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Folds an iCalendar content line into lines of at most 75 bytes, each
+/// continuation starting with a space, without splitting a character.
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
+
+/// Writes the entries as a standalone HTML page with an `<article>` per entry:
+/// the company as a heading (linked to its website, if any), then the
+/// position, dates, location, and responsibilities that are present. The
+/// title and subtitle, if any, start the page, and with `company_colors` each
+/// article gets a background color from [`company_color`] so entries at one
+/// company are grouped visually.
+/// 
+/// # Arguments
+/// * `output` - Destination for the page
+/// * `work_histories` - The entries to write, in output order
+/// * `options` - Output options
+/// 
+/// # Returns
+/// * `Result<()>` - Ok if writing succeeds, Error otherwise
+pub fn write_html<W: Write>(output: &mut W, work_histories: &[WorkHistory], options: &Options) -> Result<()> {
+    let fmt = options.date_format();
+    writeln!(output, "<!DOCTYPE html>")?;
+    writeln!(output, "<html lang=\"en\">")?;
+    writeln!(output, "<head>")?;
+    writeln!(output, "<meta charset=\"utf-8\">")?;
+    writeln!(output, "<title>{}</title>", html_escape(options.title.as_deref().unwrap_or("Work History")))?;
+    writeln!(output, "</head>")?;
+    writeln!(output, "<body>")?;
+    if let Some(title) = &options.title {
+        writeln!(output, "<h1>{}</h1>", html_escape(title))?;
+    }
+    if let Some(subtitle) = &options.subtitle {
+        writeln!(output, "<p>{}</p>", html_escape(subtitle))?;
+    }
+
+    for history in work_histories {
+        let company = history.display_company();
+        if options.company_colors {
+            writeln!(output, "<article style=\"background-color: {}\">", company_color(company))?;
+        } else {
+            writeln!(output, "<article>")?;
+        }
+        match &history.company_url {
+            Some(url) => writeln!(output, "<h2><a href=\"{}\">{}</a></h2>", html_escape(url), html_escape(company))?,
+            None => writeln!(output, "<h2>{}</h2>", html_escape(company))?,
+        }
+        writeln!(output, "<p>{}</p>", html_escape(&history.position))?;
+        let dates = history.date_range_string(&fmt);
+        for line in [dates.as_str(), &history.location, &history.responsibilities] {
+            if !line.is_empty() {
+                writeln!(output, "<p>{}</p>", html_escape(line))?;
+            }
+        }
+        writeln!(output, "</article>")?;
+    }
+
+    writeln!(output, "</body>")?;
+    writeln!(output, "</html>")?;
+
+    Ok(())
+}
+
+/// Background color for a company's entries in HTML output: a light HSL
+/// color whose hue comes from a hash of the name, so a company always gets
+/// the same color.
+/// 
+/// # Arguments
+/// * `company` - The company name, as shown in the output
+/// 
+/// # Returns
+/// * `String` - The CSS color, e.g. "hsl(212, 70%, 90%)"
+pub fn company_color(company: &str) -> String {
+    let digest = Sha256::digest(company.as_bytes());
+    let hue = u16::from_be_bytes([digest[0], digest[1]]) % 360;
+    format!("hsl({}, 70%, 90%)", hue)
+}
+
+/// Escapes the characters that are special in HTML text and attributes.
+fn html_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Writes an example input CSV for `--emit-template`: a header naming every
+/// known field (see [`Field::name`]), so it always matches the parser, and
+/// one row of example values.
+/// 
+/// # Arguments
+/// * `output` - Destination for the CSV
+/// 
+/// # Returns
+/// * `Result<()>` - Ok if writing succeeds, Error otherwise
+pub fn write_template<W: Write>(output: &mut W) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(output);
+    writer.write_record(Field::ALL.map(Field::name))?;
+    writer.write_record(Field::ALL.map(Field::example))?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Writes the entries in the requested output format.
+/// 
+/// With `ascii_only`, the text the writers add themselves (the dash between
+/// dates and in the contents, and the `--icons`) is ASCII too; the fields
+/// are expected to have been transliterated already with [`transliterate`].
+/// 
+/// # Arguments
+/// * `output` - Destination for the formatted output
+/// * `work_histories` - The entries to write, in output order
+/// * `options` - Output options, including the format
+/// 
+/// # Returns
+/// * `Result<()>` - Ok if writing succeeds, Error otherwise
+pub fn write_output<W: Write>(output: &mut W, work_histories: &[WorkHistory], options: &Options) -> Result<()> {
+    write_output_as(output, work_histories, options, options.format)
+}
+
+/// Writes the entries in the given output format, which may differ from
+/// `options.format` when several formats are written at once.
+/// 
+/// With `output_template`, the formatted entries take the place of the
+/// template's `{{entries}}` placeholder.
+/// 
+/// # Arguments
+/// * `output` - Destination for the formatted output
+/// * `work_histories` - The entries to write, in output order
+/// * `options` - Output options
+/// * `format` - The format to write
+/// 
+/// # Returns
+/// * `Result<()>` - Ok if writing succeeds, Error otherwise
+pub fn write_output_as<W: Write>(
+    output: &mut W,
+    work_histories: &[WorkHistory],
+    options: &Options,
+    format: OutputFormat,
+) -> Result<()> {
+    if let Some(template) = &options.output_template {
+        let mut formatted = Vec::new();
+        write_format(&mut formatted, work_histories, options, format)?;
+        output.write_all(&fill_document_template(template, &formatted)?)?;
+        return Ok(());
+    }
+
+    write_format(output, work_histories, options, format)
+}
+
+/// Placeholder in an `--output-template-file` for the formatted entries.
+pub const ENTRIES_PLACEHOLDER: &str = "{{entries}}";
+
+/// Surrounds the formatted entries with the text before and after the first
+/// `{{entries}}` placeholder of a document template, as they are.
+/// 
+/// # Arguments
+/// * `template` - The document template
+/// * `entries` - The formatted entries
+/// 
+/// # Returns
+/// * `Result<Vec<u8>>` - The whole document
+/// 
+/// # Errors
+/// Returns an error if the template has no `{{entries}}` placeholder
+pub fn fill_document_template(template: &str, entries: &[u8]) -> Result<Vec<u8>> {
+    let (header, footer) = template
+        .split_once(ENTRIES_PLACEHOLDER)
+        .ok_or_else(|| anyhow!("The output template has no {} placeholder", ENTRIES_PLACEHOLDER))?;
+    Ok([header.as_bytes(), entries, footer.as_bytes()].concat())
+}
+
+/// Writes the entries in the requested output format, as given.
+fn write_format<W: Write>(
+    output: &mut W,
+    work_histories: &[WorkHistory],
+    options: &Options,
+    format: OutputFormat,
+) -> Result<()> {
+    match format {
+        OutputFormat::Text => write_text(output, work_histories, options),
+        OutputFormat::Locations => write_locations(output, work_histories),
+        OutputFormat::Json => write_json(output, work_histories, options.pretty),
+        OutputFormat::Jsonl => write_json_lines(output, work_histories),
+        OutputFormat::Prose => write_prose(output, work_histories, options),
+        OutputFormat::Csv => write_csv(output, work_histories, options),
+        OutputFormat::Table => write_table(output, work_histories, options),
+        OutputFormat::Timeline => write_timeline(output, work_histories, options),
+        OutputFormat::Ics => write_ics(output, work_histories, options),
+        OutputFormat::Html => write_html(output, work_histories, options),
+    }
+}
+
+/// Writes the optional title (underlined with `=`) and subtitle that start a
+/// text document, followed by a blank line. Nothing is written without them.
+/// 
+/// # Arguments
+/// * `output` - Destination for the formatted text
+/// * `options` - Output options, holding the title and subtitle
+/// 
+/// # Returns
+/// * `Result<()>` - Ok if writing succeeds, Error otherwise
+fn write_title_block<W: Write>(output: &mut W, options: &Options) -> Result<()> {
+    if let Some(title) = &options.title {
+        writeln!(output, "{}", title)?;
+        writeln!(output, "{}", "=".repeat(title.chars().count()))?;
+    }
+    if let Some(subtitle) = &options.subtitle {
+        writeln!(output, "{}", subtitle)?;
+    }
+    if options.title.is_some() || options.subtitle.is_some() {
+        writeln!(output)?;
+    }
+
+    Ok(())
+}
+
+/// Icon written before a field's line of a text entry with `--icons`.
+fn field_icon(field: Field) -> Option<&'static str> {
+    match field {
+        Field::Company => Some("🏢"),
+        Field::Position => Some("💼"),
+        Field::StartDate | Field::EndDate => Some("📅"),
+        Field::Address => Some("📍"),
+        _ => None,
+    }
+}
+
+/// Writes a single work history entry in the plain text format.
+/// 
+/// # Arguments
+/// * `output` - Destination for the formatted text
+/// * `index` - Zero-based position of the entry in the output
+/// * `total` - Total number of entries being written
+/// * `history` - The entry to write
+/// * `options` - Output options
+/// 
+/// # Returns
+/// * `Result<()>` - Ok if writing succeeds, Error otherwise
+fn write_text_entry<W: Write>(
+    output: &mut W,
+    index: usize,
+    total: usize,
+    history: &WorkHistory,
+    options: &Options,
+) -> Result<()> {
+    let icon = |field: Field| match field_icon(field) {
+        Some(icon) if options.icons && !options.ascii_only => format!("{} ", icon),
+        _ => String::new(),
+    };
+
+    writeln!(output, "{}", entry_heading(index, total, options))?;
+    writeln!(output, "{}Company: {}", icon(Field::Company), history.display_company())?;
+    if let Some(url) = &history.company_url {
+        writeln!(output, "Website: {}", url)?;
+    }
+    writeln!(output, "{}Position: {}", icon(Field::Position), history.position)?;
+    if let Some(department) = &history.department {
+        writeln!(output, "Department: {}", department)?;
+    }
+    if options.include_type {
+        if let Some(employment_type) = &history.employment_type {
+            writeln!(output, "Employment Type: {}", employment_type)?;
+        }
+    }
+    if let Some(start_date) = history.start_date {
+        // The MM/01/YYYY dates are kept unless --date-format asks otherwise
+        let (start, end) = if options.date_pattern.is_some() {
+            let fmt = options.date_format();
+            (fmt.format(start_date), history.end_date.map_or(fmt.present_label.clone(), |end| fmt.format(end)))
+        } else {
+            (format_date(start_date), format_end_date(history.end_date, options.present_label()))
+        };
+        writeln!(output, "{}Start Date: {}", icon(Field::StartDate), start)?;
+        if options.relative_dates {
+            writeln!(output, "{}End Date: {} ({})", icon(Field::EndDate), end, relative_end(history.end_date, options.today()))?;
+        } else {
+            writeln!(output, "{}End Date: {}", icon(Field::EndDate), end)?;
+        }
+    }
+    if options.show_duration {
+        if let Some(months) = history.tenure_months(options.today()) {
+            writeln!(output, "Duration: {}", format_months(months))?;
+        }
+    }
+    writeln!(output, "{}Location: {}", icon(Field::Address), history.location)?;
+    if options.include_zip {
+        if let Some(postal_code) = &history.postal_code {
+            writeln!(output, "Postal Code: {}", postal_code)?;
+        }
+    }
+    if options.aggregate_responsibilities != Some(AggregateResponsibilities::Only) {
+        write_responsibilities(output, &history.responsibilities, options)?;
+    }
+    if options.include_keywords && !history.keywords.is_empty() {
+        let mut keywords: Vec<&str> = history.keywords.iter().map(String::as_str).collect();
+        if options.sort_keywords {
+            keywords.sort_by_cached_key(|keyword| keyword.to_lowercase());
+        }
+        writeln!(output, "Keywords: {}", keywords.join(", "))?;
+    }
+    if options.include_salary {
+        if let Some(salary) = &history.salary {
+            writeln!(output, "Salary: {}", format_salary(salary))?;
+        }
+    }
+    if options.include_reference {
+        if let Some(contact) = &history.reference_contact {
+            let contact = if options.normalize_phone { normalize_phone(contact) } else { contact.clone() };
+            match &history.reference_name {
+                Some(name) => writeln!(output, "Reference: {}, {}", name, contact)?,
+                None => writeln!(output, "Reference: {}", contact)?,
+            }
+        }
+    }
+    if !options.no_blank_lines && options.separator.is_none() {
+        writeln!(output)?; // Empty line between entries
+    }
+
+    Ok(())
+}
+
+/// Describes how long ago a job ended, for `--relative-dates`, e.g. "ended
+/// 3 years ago", "ended 5 months ago", or "ongoing". Whole years are used
+/// from a year on; an end date later than today "ends in" the future.
+/// 
+/// # Arguments
+/// * `end_date` - The entry's end date, or `None` for an ongoing job
+/// * `today` - The date to count back from
+/// 
+/// # Returns
+/// * `String` - The annotation, without parentheses
+fn relative_end(end_date: Option<NaiveDate>, today: NaiveDate) -> String {
+    let Some(end_date) = end_date else {
+        return "ongoing".to_string();
+    };
+    let months = month_number(today) - month_number(end_date);
+    let humanized = |months: i32| match months {
+        0..=11 => format_months(months),
+        _ => format_months(months / 12 * 12),
+    };
+    match months {
+        0 => "ended this month".to_string(),
+        months if months > 0 => format!("ended {} ago", humanized(months)),
+        months => format!("ends in {}", humanized(-months)),
+    }
+}
+
+/// Writes the "Responsibilities:" line of a text entry.
+/// 
+/// Lines within the responsibilities (from a multiline cell) are joined with
+/// spaces, or with `multiline_bullets` written below the label as "- "
+/// bullets, one per line, ordered by `responsibilities_sort`. With
+/// `numbered_responsibilities` the clauses between semicolons are split out
+/// too and numbered "1. ", "2. ", and so on. `tidy` applies to each item.
+/// 
+/// # Arguments
+/// * `output` - Destination for the formatted text
+/// * `responsibilities` - The entry's responsibilities
+/// * `options` - Output options
+/// 
+/// # Returns
+/// * `Result<()>` - Ok if writing succeeds, Error otherwise
+fn write_responsibilities<W: Write>(output: &mut W, responsibilities: &str, options: &Options) -> Result<()> {
+    let tidy = |text: &str| if options.tidy { tidy_sentence(text) } else { text.to_string() };
+    let mut lines = responsibility_lines(responsibilities);
+    if options.numbered_responsibilities {
+        lines = lines.iter().flat_map(|line| line.split(';')).map(str::trim).filter(|clause| !clause.is_empty()).collect();
+    }
+
+    if (options.multiline_bullets || options.numbered_responsibilities) && lines.len() > 1 {
+        match options.responsibilities_sort {
+            ResponsibilitiesSort::None => {}
+            ResponsibilitiesSort::Alpha => lines.sort_by_cached_key(|line| line.to_lowercase()),
+            ResponsibilitiesSort::Length => lines.sort_by_key(|line| line.chars().count()),
+        }
+        writeln!(output, "Responsibilities:")?;
+        for (number, line) in lines.into_iter().enumerate() {
+            if options.numbered_responsibilities {
+                writeln!(output, "{}. {}", number + 1, tidy(line))?;
+            } else {
+                writeln!(output, "- {}", tidy(line))?;
+            }
+        }
+    } else {
+        writeln!(output, "Responsibilities: {}", tidy(&lines.join(" ")))?;
+    }
+
+    Ok(())
+}
+
+/// Splits responsibilities into their non-blank, trimmed lines.
+fn responsibility_lines(responsibilities: &str) -> Vec<&str> {
+    responsibilities
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Returns the directory an output path is in, using "." for a bare file name.
+pub fn output_directory(output_path: &Path) -> &Path {
+    match output_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
+
+/// Creates the directory an output path is in, along with any missing
+/// parent directories, for `--create-dirs`.
+/// 
+/// # Arguments
+/// * `output_path` - Path where the output file will be written
+/// 
+/// # Returns
+/// * `Result<()>` - Ok if the directory exists afterwards, Error otherwise
+pub fn create_output_directory(output_path: &Path) -> Result<()> {
+    let directory = output_directory(output_path);
+    fs::create_dir_all(directory)
+        .with_context(|| format!("Failed to create output directory: {}", directory.display()))
+}
+
+/// A fully formatted output file, buffered in memory before it is written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputFile {
+    /// Where the file will be written
+    pub path: PathBuf,
+    /// The formatted bytes
+    pub contents: Vec<u8>,
+}
+
+impl OutputFile {
+    /// Writes the buffered contents to the file's path.
+    pub fn write(&self) -> Result<()> {
+        fs::write(&self.path, &self.contents)
+            .with_context(|| format!("Failed to create output file: {}", self.path.display()))
+    }
+
+    /// Builds a unified diff from the file currently at the path (treated as
+    /// empty if it doesn't exist) to the buffered contents.
+    /// 
+    /// # Returns
+    /// * `Result<String>` - The diff, empty when nothing changed
+    pub fn diff_against_existing(&self) -> Result<String> {
+        let existing = match fs::read(&self.path) {
+            Ok(existing) => existing,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => {
+                return Err(err).with_context(|| format!("Failed to read existing output: {}", self.path.display()));
+            }
+        };
+        Ok(unified_diff(
+            &String::from_utf8_lossy(&existing),
+            &String::from_utf8_lossy(&self.contents),
+            &self.path.display().to_string(),
+        ))
+    }
+}
+
+/// Builds a unified diff between two versions of an output file.
+/// 
+/// # Arguments
+/// * `old` - The current contents
+/// * `new` - The newly formatted contents
+/// * `name` - File name shown in the diff header
+/// 
+/// # Returns
+/// * `String` - The diff, empty when the contents are equal
+pub fn unified_diff(old: &str, new: &str, name: &str) -> String {
+    if old == new {
+        return String::new();
+    }
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .header(name, name)
+        .to_string()
+}
+
+/// The UTF-8 byte order mark written by `--output-bom`.
+pub const UTF8_BOM: [u8; 3] = [0xEF, 0xBB, 0xBF];
+
+/// Buffers an output file, starting it with a UTF-8 byte order mark when
+/// `output_bom` is set.
+fn output_file(path: PathBuf, mut contents: Vec<u8>, options: &Options) -> OutputFile {
+    if options.output_bom {
+        contents.splice(0..0, UTF8_BOM);
+    }
+    OutputFile { path, contents }
+}
+
+/// Formats all entries into a single output file.
+/// 
+/// # Arguments
+/// * `path` - Where the file will be written
+/// * `work_histories` - The entries to write, in output order
+/// * `options` - Output options
+/// 
+/// # Returns
+/// * `Result<OutputFile>` - The buffered output file
+pub fn render_text_file(path: &Path, work_histories: &[WorkHistory], options: &Options) -> Result<OutputFile> {
+    let mut contents = Vec::new();
+    write_output(&mut contents, work_histories, options)?;
+    Ok(output_file(path.to_path_buf(), contents, options))
+}
+
+/// Formats all entries once per requested format, naming each file after
+/// the output path with the format's extension (`out.txt` and `out.json`
+/// for `--format text,json`).
+/// 
+/// # Arguments
+/// * `path` - The output path the file names are derived from
+/// * `work_histories` - The entries to write, in output order
+/// * `options` - Output options, holding the formats
+/// 
+/// # Returns
+/// * `Result<Vec<OutputFile>>` - The buffered output files, in format order
+/// 
+/// # Errors
+/// Returns an error if two formats would write the same file
+pub fn render_format_files(path: &Path, work_histories: &[WorkHistory], options: &Options) -> Result<Vec<OutputFile>> {
+    let mut files: Vec<OutputFile> = Vec::new();
+
+    for format in options.formats() {
+        let path = path.with_extension(format.extension());
+        if files.iter().any(|file| file.path == path) {
+            return Err(anyhow!("Two of the requested formats would both be written to {}", path.display()));
+        }
+        let mut contents = Vec::new();
+        write_output_as(&mut contents, work_histories, options, format)?;
+        files.push(output_file(path, contents, options));
+    }
+
+    Ok(files)
+}
+
+/// Formats each entry as its own file, `work_history_N.txt`, in a directory,
+/// numbered like the headings from `start_numbering_at` if it is set.
+/// 
+/// # Arguments
+/// * `directory` - Directory the files will be written to
+/// * `work_histories` - The entries to write, in output order
+/// * `options` - Output options
+/// 
+/// # Returns
+/// * `Result<Vec<OutputFile>>` - The buffered output files, in order
+pub fn render_split_files(directory: &Path, work_histories: &[WorkHistory], options: &Options) -> Result<Vec<OutputFile>> {
+    let total = work_histories.len();
+    let first = options.start_numbering_at.unwrap_or(1);
+    let mut files = Vec::with_capacity(total);
+
+    for (index, history) in work_histories.iter().enumerate() {
+        let mut contents = Vec::new();
+        write_text_entry(&mut contents, index, total, history, options)?;
+        if let Some(template) = &options.output_template {
+            contents = fill_document_template(template, &contents)?;
+        }
+        files.push(output_file(directory.join(format!("work_history_{}.txt", index + first)), contents, options));
+    }
+
+    Ok(files)
+}
+
+/// Computes the SHA-256 digest of formatted output as lowercase hex.
+/// 
+/// # Arguments
+/// * `contents` - The formatted bytes
+/// 
+/// # Returns
+/// * `String` - The 64-character hex digest
+pub fn sha256_hex(contents: &[u8]) -> String {
+    Sha256::digest(contents)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Capitalizes the first letter of a text and makes it end with a period.
+/// 
+/// Trailing whitespace and stray separators (`,`, `;`, `:`, `-`) are
+/// removed first; text already ending in `.`, `!`, or `?` keeps its ending.
+/// 
+/// # Arguments
+/// * `text` - The text to tidy
+/// 
+/// # Returns
+/// * `String` - The tidied text, or an empty string for blank text
+pub fn tidy_sentence(text: &str) -> String {
+    let trimmed = text
+        .trim()
+        .trim_end_matches(|c: char| matches!(c, ',' | ';' | ':' | '-') || c.is_whitespace());
+    let mut chars = trimmed.chars();
+    let mut tidied = match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+        None => return String::new(),
+    };
+
+    if !tidied.ends_with(['.', '!', '?']) {
+        tidied.push('.');
+    }
+    tidied
+}
+
+/// Formats a salary as dollars with thousands separators.
+/// 
+/// Existing `$` signs, commas, and whitespace are ignored, so "85000",
+/// "$85,000", and "85,000.50" are all accepted. Values that aren't a plain
+/// number (e.g. "85k/yr") are written as given.
+/// 
+/// # Arguments
+/// * `salary` - The salary as written in the input
+/// 
+/// # Returns
+/// * `String` - The formatted salary, e.g. "$85,000"
+fn format_salary(salary: &str) -> String {
+    let digits: String = salary
+        .chars()
+        .filter(|c| *c != '$' && *c != ',' && !c.is_whitespace())
+        .collect();
+    let (whole, cents) = match digits.split_once('.') {
+        Some((whole, cents)) => (whole, Some(cents)),
+        None => (digits.as_str(), None),
+    };
+
+    let is_number = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit());
+    if !is_number(whole) || !cents.is_none_or(is_number) {
+        return salary.trim().to_string();
+    }
+
+    let whole = whole.trim_start_matches('0');
+    let whole = if whole.is_empty() { "0" } else { whole };
+    let mut grouped = String::new();
+    for (index, digit) in whole.chars().enumerate() {
+        if index > 0 && (whole.len() - index) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+
+    match cents {
+        Some(cents) => format!("${}.{}", grouped, cents),
+        None => format!("${}", grouped),
+    }
+}
+
+/// Replaces typographic punctuation from word processors with its plain
+/// ASCII equivalent: curly single and double quotes with `'` and `"`, en
+/// dashes (and other short dashes) with `-`, em dashes with `--`, and
+/// ellipses with `...`. Everything else is kept.
+/// 
+/// # Arguments
+/// * `text` - The text to normalize
+/// 
+/// # Returns
+/// * `String` - The text with plain punctuation
+pub fn normalize_punctuation(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' | '\u{2032}' => normalized.push('\''),
+            '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' | '\u{2033}' => normalized.push('"'),
+            '\u{2010}' | '\u{2011}' | '\u{2012}' | '\u{2013}' | '\u{2212}' => normalized.push('-'),
+            '\u{2014}' | '\u{2015}' => normalized.push_str("--"),
+            '\u{2026}' => normalized.push_str("..."),
+            _ => normalized.push(c),
+        }
+    }
+    normalized
+}
+
+/// Transliterates text to ASCII for `--ascii-only`: accented letters lose
+/// their accents ("Café" becomes "Cafe"), typographic quotes and dashes
+/// become plain ones, and characters with no equivalent become "?".
+/// 
+/// # Arguments
+/// * `text` - The text to transliterate
+/// 
+/// # Returns
+/// * `String` - The text in ASCII
+pub fn transliterate(text: &str) -> String {
+    let mut ascii = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_ascii() {
+            ascii.push(c);
+        } else {
+            ascii.push_str(deunicode_char(c).unwrap_or("?"));
+        }
+    }
+    ascii
+}
+
+/// Formats a US phone number as "(XXX) XXX-XXXX".
+/// 
+/// The number may be written with spaces, dashes, dots, and parentheses,
+/// and with a leading "+1" or "1" country code. Anything else, such as an
+/// email address, a non-US number, or an extension, is returned trimmed but
+/// otherwise as given.
+/// 
+/// # Arguments
+/// * `phone` - The phone number as written in the input
+/// 
+/// # Returns
+/// * `String` - The formatted phone number, e.g. "(555) 123-4567"
+pub fn normalize_phone(phone: &str) -> String {
+    let phone = phone.trim();
+    if !phone.chars().all(|c| c.is_ascii_digit() || " -.()+".contains(c)) {
+        return phone.to_string();
+    }
+
+    let digits: String = phone.chars().filter(char::is_ascii_digit).collect();
+    let national = match digits.len() {
+        10 if !phone.starts_with('+') => digits.as_str(),
+        11 if phone.starts_with("+1") || (digits.starts_with('1') && !phone.contains('+')) => &digits[1..],
+        _ => return phone.to_string(),
+    };
+
+    format!("({}) {}-{}", &national[..3], &national[3..6], &national[6..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+    use crate::test_support::entry;
+    use std::env;
+
+    #[test]
+    fn write_text_formats_entries() {
+        let mut output = Vec::new();
+        write_text(&mut output, &[entry("Acme", "03/15/2020", "07/04/2021")], &Options::default()).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Work History 1\n\
+            Company: Acme\n\
+            Position: Engineer\n\
+            Start Date: 03/01/2020\n\
+            End Date: 07/01/2021\n\
+            Location: Springfield, IL\n\
+            Responsibilities: Build things\n\
+            \n"
+        );
+    }
+
+    #[test]
+    fn no_blank_lines_omits_entry_separators() {
+        let histories = vec![
+            entry("Acme", "03/15/2020", "Present"),
+            entry("Other", "01/01/2018", "03/01/2020"),
+        ];
+        let options = Options { no_blank_lines: true, ..Options::default() };
+
+        let mut output = Vec::new();
+        write_text(&mut output, &histories, &options).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Work History 1\n\
+            Company: Acme\n\
+            Position: Engineer\n\
+            Start Date: 03/01/2020\n\
+            End Date: Present\n\
+            Location: Springfield, IL\n\
+            Responsibilities: Build things\n\
+            Work History 2\n\
+            Company: Other\n\
+            Position: Engineer\n\
+            Start Date: 01/01/2018\n\
+            End Date: 03/01/2020\n\
+            Location: Springfield, IL\n\
+            Responsibilities: Build things\n"
+        );
+    }
+
+    #[test]
+    fn ongoing_job_is_written_as_present() {
+        let mut output = Vec::new();
+        write_text(&mut output, &[entry("Acme", "03/15/2020", "Present")], &Options::default()).unwrap();
+        assert!(String::from_utf8(output).unwrap().contains("End Date: Present\n"));
+    }
+
+    #[test]
+    fn ongoing_job_uses_custom_present_label() {
+        let options = Options { present_label: Some("Current".to_string()), ..Options::default() };
+        let mut output = Vec::new();
+        write_text(&mut output, &[entry("Acme", "03/15/2020", "Present")], &options).unwrap();
+        assert!(String::from_utf8(output).unwrap().contains("End Date: Current\n"));
+    }
+
+    #[test]
+    fn chronological_labels_count_down_from_most_recent() {
+        let histories = vec![
+            entry("Newest", "01/01/2022", "Present"),
+            entry("Middle", "01/01/2020", "01/01/2022"),
+            entry("Oldest", "01/01/2018", "01/01/2020"),
+        ];
+        let options = Options { chronological_labels: true, ..Options::default() };
+
+        let mut output = Vec::new();
+        write_text(&mut output, &histories, &options).unwrap();
+        let headings: Vec<String> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .filter(|line| line.starts_with("Job #"))
+            .map(String::from)
+            .collect();
+        assert_eq!(headings, ["Job #3 of 3 (most recent)", "Job #2 of 3", "Job #1 of 3"]);
+    }
+
+    #[test]
+    fn preview_keeps_the_full_total_in_labels() {
+        let histories = vec![
+            entry("Newest", "01/01/2022", "Present"),
+            entry("Middle", "01/01/2020", "01/01/2022"),
+            entry("Oldest", "01/01/2018", "01/01/2020"),
+        ];
+        let options = Options { chronological_labels: true, preview: Some(2), ..Options::default() };
+
+        let mut output = Vec::new();
+        write_text(&mut output, &histories, &options).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.contains("Job #2 of 3\n"));
+        assert!(!text.contains("Oldest"));
+    }
+
+    #[test]
+    fn split_per_entry_writes_one_file_per_entry() {
+        let directory = env::temp_dir().join(format!("work_history_split_{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let histories = vec![
+            entry("Newest", "01/01/2022", "Present"),
+            entry("Oldest", "01/01/2018", "01/01/2020"),
+        ];
+
+        let files = render_split_files(&directory, &histories, &Options::default()).unwrap();
+        let paths: Vec<&Path> = files.iter().map(|file| file.path.as_path()).collect();
+        assert_eq!(paths, [directory.join("work_history_1.txt"), directory.join("work_history_2.txt")]);
+
+        for file in &files {
+            file.write().unwrap();
+        }
+        let second = fs::read_to_string(&files[1].path).unwrap();
+        assert!(second.starts_with("Work History 2\nCompany: Oldest\n"));
+        assert!(!second.contains("Newest"));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn sha256_hex_matches_known_digest() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn hash_is_computed_over_the_rendered_text() {
+        let histories = vec![entry("Acme", "01/01/2020", "Present")];
+        let file = render_text_file(Path::new("out.txt"), &histories, &Options::default()).unwrap();
+
+        let mut expected = Vec::new();
+        write_text(&mut expected, &histories, &Options::default()).unwrap();
+        assert_eq!(file.contents, expected);
+        assert_eq!(sha256_hex(&file.contents), sha256_hex(&expected));
+    }
+
+    #[test]
+    fn format_salary_adds_thousands_separators() {
+        assert_eq!(format_salary("85000"), "$85,000");
+        assert_eq!(format_salary("$85,000"), "$85,000");
+        assert_eq!(format_salary(" 1234567.50 "), "$1,234,567.50");
+        assert_eq!(format_salary("999"), "$999");
+        assert_eq!(format_salary("0"), "$0");
+    }
+
+    #[test]
+    fn format_salary_keeps_non_numeric_values() {
+        assert_eq!(format_salary("85k/yr"), "85k/yr");
+        assert_eq!(format_salary("$"), "$");
+        assert_eq!(format_salary("1.2.3"), "1.2.3");
+    }
+
+    #[test]
+    fn salary_line_only_written_when_included() {
+        let mut history = entry("Acme", "01/01/2020", "01/01/2021");
+        history.salary = Some("120000".to_string());
+        let blank = entry("Other", "01/01/2019", "01/01/2020");
+
+        let mut output = Vec::new();
+        write_text(&mut output, &[history, blank], &Options::default()).unwrap();
+        assert!(!String::from_utf8(output).unwrap().contains("Salary:"));
+
+        let mut history = entry("Acme", "01/01/2020", "01/01/2021");
+        history.salary = Some("120000".to_string());
+        let blank = entry("Other", "01/01/2019", "01/01/2020");
+        let options = Options { include_salary: true, ..Options::default() };
+
+        let mut output = Vec::new();
+        write_text(&mut output, &[history, blank], &options).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.contains("Responsibilities: Build things\nSalary: $120,000\n"));
+        assert_eq!(text.matches("Salary:").count(), 1);
+    }
+
+    #[test]
+    fn locations_format_lists_each_location_once_sorted() {
+        let mut first = entry("Acme", "01/01/2020", "Present");
+        first.location = "Springfield, IL".to_string();
+        let mut second = entry("Other", "01/01/2018", "01/01/2020");
+        second.location = "Austin, TX".to_string();
+        let mut third = entry("Third", "01/01/2016", "01/01/2018");
+        third.location = "Springfield, IL".to_string();
+        let options = Options { format: OutputFormat::Locations, ..Options::default() };
+
+        let mut output = Vec::new();
+        write_output(&mut output, &[first, second, third], &options).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "Austin, TX\nSpringfield, IL\n");
+    }
+
+    #[test]
+    fn jsonl_format_writes_one_object_per_line() {
+        let histories = vec![
+            entry("Acme", "03/15/2020", "Present"),
+            entry("Other", "01/01/2018", "03/01/2020"),
+        ];
+        let options = Options { format: OutputFormat::Jsonl, ..Options::default() };
+
+        let mut output = Vec::new();
+        write_output(&mut output, &histories, &options).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        let lines: Vec<serde_json::Value> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["company"], "Acme");
+        assert_eq!(lines[0]["start_date"], "2020-03-15");
+        assert!(lines[0]["end_date"].is_null());
+        assert_eq!(lines[1]["end_date"], "2020-03-01");
+    }
+
+    #[test]
+    fn tidy_sentence_capitalizes_and_punctuates_once() {
+        assert_eq!(tidy_sentence("built things"), "Built things.");
+        assert_eq!(tidy_sentence("built things, ;"), "Built things.");
+        assert_eq!(tidy_sentence("Built things."), "Built things.");
+        assert_eq!(tidy_sentence("Shipped it!"), "Shipped it!");
+        assert_eq!(tidy_sentence("  "), "");
+    }
+
+    #[test]
+    fn tidy_only_changes_the_written_responsibilities() {
+        let mut history = entry("Acme", "01/01/2020", "Present");
+        history.responsibilities = "led the team;".to_string();
+        let options = Options { tidy: true, ..Options::default() };
+
+        let mut output = Vec::new();
+        write_text(&mut output, std::slice::from_ref(&history), &options).unwrap();
+        assert!(String::from_utf8(output).unwrap().contains("Responsibilities: Led the team.\n"));
+        assert_eq!(history.responsibilities, "led the team;");
+    }
+
+    #[test]
+    fn unified_diff_shows_changed_lines() {
+        let diff = unified_diff("Company: Acme\nPosition: Engineer\n", "Company: Acme\nPosition: Lead\n", "out.txt");
+        assert!(diff.starts_with("--- out.txt\n+++ out.txt\n"));
+        assert!(diff.contains("-Position: Engineer\n+Position: Lead\n"));
+        assert_eq!(unified_diff("same\n", "same\n", "out.txt"), "");
+    }
+
+    #[test]
+    fn ascii_only_transliterates_every_field() {
+        let mut history = entry("Café Zürich", "01/01/2020", "Present");
+        history.location = "Zürich, ZH".to_string();
+        history.responsibilities = "Ran the “front of house” — daily".to_string();
+        history.rewrite_text(transliterate);
+        let options = Options { ascii_only: true, icons: true, toc: true, ..Options::default() };
+
+        let mut output = Vec::new();
+        write_output(&mut output, &[history], &options).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.is_ascii());
+        assert!(text.contains("Company: Cafe Zurich\n"));
+        assert!(text.contains("Location: Zurich, ZH\n"));
+        assert!(text.contains("Responsibilities: Ran the \"front of house\" -- daily\n"));
+        assert!(text.contains("Cafe Zurich -- 01/2020 - Present\n"));
+    }
+
+    #[test]
+    fn separator_is_written_only_between_entries() {
+        let histories = vec![
+            entry("Acme", "03/15/2020", "Present"),
+            entry("Other", "01/01/2018", "03/01/2020"),
+        ];
+        let options = Options { separator: Some("---".to_string()), ..Options::default() };
+
+        let mut output = Vec::new();
+        write_text(&mut output, &histories, &options).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.contains("Responsibilities: Build things\n---\nWork History 2\n"));
+        assert!(text.ends_with("Responsibilities: Build things\n"));
+        assert_eq!(text.matches("---").count(), 1);
+    }
+
+    #[test]
+    fn undated_entries_have_no_date_lines() {
+        let mut output = Vec::new();
+        write_text(&mut output, &[entry("Food Bank", "", "")], &Options::default()).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.contains("Position: Engineer\nLocation: Springfield, IL\n"));
+        assert!(!text.contains("Date:"));
+    }
+
+    #[test]
+    fn prose_describes_finished_and_ongoing_jobs() {
+        let mut finished = entry("Acme Corp", "01/15/2020", "03/01/2022");
+        finished.position = "Software Engineer".to_string();
+        finished.responsibilities = "Built the billing system.".to_string();
+        let mut ongoing = entry("Initech", "04/01/2022", "Present");
+        ongoing.position = "Engineering Manager".to_string();
+        ongoing.responsibilities = String::new();
+        let options = Options { format: OutputFormat::Prose, ..Options::default() };
+
+        let mut output = Vec::new();
+        write_output(&mut output, &[ongoing, finished], &options).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Since 04/2022, I have worked as an Engineering Manager at Initech in Springfield, IL.\n\
+            \n\
+            From 01/2020 to 03/2022, I worked as a Software Engineer at Acme Corp in Springfield, IL, \
+            where I built the billing system.\n"
+        );
+    }
+
+    #[test]
+    fn prose_leaves_out_empty_clauses() {
+        let mut history = entry("Acme", "01/01/2020", "01/01/2021");
+        history.position = String::new();
+        history.location = String::new();
+        history.responsibilities = "Ran AWS migrations".to_string();
+        let text = prose_paragraph(&history, &DateFormat::default());
+        assert_eq!(text, "From 01/2020 to 01/2021, I worked at Acme, where I ran AWS migrations.");
+
+        assert_eq!(lowercase_first("AWS migrations"), "AWS migrations");
+        assert_eq!(
+            prose_paragraph(&entry("Food Bank", "", ""), &DateFormat::default()),
+            "I worked as an Engineer at Food Bank in Springfield, IL, where I build things."
+        );
+    }
+
+    #[test]
+    fn display_name_is_shown_instead_of_company() {
+        let mut history = entry("Alphabet Inc.", "01/01/2020", "Present");
+        history.display_name = Some("Google".to_string());
+
+        let mut output = Vec::new();
+        write_text(&mut output, std::slice::from_ref(&history), &Options::default()).unwrap();
+        assert!(String::from_utf8(output).unwrap().contains("Company: Google\n"));
+
+        let options = Options { format: OutputFormat::Jsonl, ..Options::default() };
+        let mut output = Vec::new();
+        write_output(&mut output, &[history], &options).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(json["company"], "Alphabet Inc.");
+        assert_eq!(json["display_name"], "Google");
+    }
+
+    #[test]
+    fn json_is_compact_unless_pretty() {
+        let histories = vec![entry("Acme", "03/15/2020", "Present")];
+        let options = Options { format: OutputFormat::Json, ..Options::default() };
+
+        let mut output = Vec::new();
+        write_output(&mut output, &histories, &options).unwrap();
+        let compact = String::from_utf8(output).unwrap();
+        assert!(compact.starts_with("[{\"company\":\"Acme\","));
+        assert_eq!(compact.lines().count(), 1);
+
+        let options = Options { format: OutputFormat::Json, pretty: true, ..Options::default() };
+        let mut output = Vec::new();
+        write_output(&mut output, &histories, &options).unwrap();
+        let pretty = String::from_utf8(output).unwrap();
+        assert!(pretty.starts_with("[\n  {\n    \"company\": \"Acme\",\n"));
+
+        let parsed: serde_json::Value = serde_json::from_str(&pretty).unwrap();
+        assert_eq!(parsed, serde_json::from_str::<serde_json::Value>(&compact).unwrap());
+    }
+
+    #[test]
+    fn title_and_subtitle_come_before_the_entries() {
+        let histories = vec![entry("Acme", "03/15/2020", "Present")];
+        let options = Options {
+            title: Some("Work History — Jane Doe".to_string()),
+            subtitle: Some("Software Engineer".to_string()),
+            ..Options::default()
+        };
+
+        let mut output = Vec::new();
+        write_text(&mut output, &histories, &options).unwrap();
+        assert!(String::from_utf8(output).unwrap().starts_with(
+            "Work History — Jane Doe\n\
+            =======================\n\
+            Software Engineer\n\
+            \n\
+            Work History 1\n"
+        ));
+
+        let mut output = Vec::new();
+        write_text(&mut output, &histories, &Options::default()).unwrap();
+        assert!(String::from_utf8(output).unwrap().starts_with("Work History 1\n"));
+    }
+
+    #[test]
+    fn csv_quote_style_controls_quoting_of_commas() {
+        let histories = vec![entry("Acme", "03/15/2020", "Present")];
+        let csv_line = |quote_style| {
+            let options = Options { format: OutputFormat::Csv, quote_style, ..Options::default() };
+            let mut output = Vec::new();
+            write_output(&mut output, &histories, &options).unwrap();
+            String::from_utf8(output).unwrap().lines().nth(1).unwrap().to_string()
+        };
+
+        assert_eq!(csv_line(None), "Acme,,Engineer,,03/15/2020,Present,\"Springfield, IL\",Build things,,,");
+        assert_eq!(
+            csv_line(Some(csv::QuoteStyle::Always)),
+            "\"Acme\",\"\",\"Engineer\",\"\",\"03/15/2020\",\"Present\",\"Springfield, IL\",\"Build things\",\"\",\"\",\"\""
+        );
+        assert_eq!(
+            csv_line(Some(csv::QuoteStyle::Never)),
+            "Acme,,Engineer,,03/15/2020,Present,Springfield, IL,Build things,,,"
+        );
+    }
+
+    #[test]
+    fn csv_output_reads_back_as_the_same_entries() {
+        let mut history = entry("Alphabet Inc.", "03/15/2020", "Present");
+        history.display_name = Some("Google".to_string());
+        history.skills = vec!["Rust".to_string(), "Go".to_string()];
+        let histories = vec![history, entry("Food Bank", "", ""), entry("Other", "01/01/2018", "03/01/2020")];
+        let options = Options { format: OutputFormat::Csv, ..Options::default() };
+
+        let mut output = Vec::new();
+        write_output(&mut output, &histories, &options).unwrap();
+        let read = crate::input::read_work_histories(output.as_slice(), &Options::default(), &mut Vec::new()).unwrap();
+
+        assert_eq!(read.len(), 3);
+        for (read, original) in read.iter().zip(&histories) {
+            assert_eq!(read.company, original.company);
+            assert_eq!(read.display_name, original.display_name);
+            assert_eq!(read.start_date, original.start_date);
+            assert_eq!(read.end_date, original.end_date);
+            assert_eq!(read.location, original.location);
+            assert_eq!(read.responsibilities, original.responsibilities);
+            assert_eq!(read.skills, original.skills);
+        }
+    }
+
+    #[test]
+    fn employment_type_line_only_written_when_included() {
+        let mut history = entry("Acme", "01/01/2020", "Present");
+        history.employment_type = Some("Contract".to_string());
+
+        let mut output = Vec::new();
+        write_text(&mut output, std::slice::from_ref(&history), &Options::default()).unwrap();
+        assert!(!String::from_utf8(output).unwrap().contains("Employment Type:"));
+
+        let options = Options { include_type: true, ..Options::default() };
+        let mut output = Vec::new();
+        write_text(&mut output, &[history, entry("Other", "01/01/2018", "01/01/2020")], &options).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.contains("Position: Engineer\nEmployment Type: Contract\nStart Date:"));
+        assert_eq!(text.matches("Employment Type:").count(), 1);
+    }
+
+    #[test]
+    fn show_duration_measures_ongoing_jobs_until_today() {
+        let histories = vec![entry("Acme", "03/15/2020", "Present"), entry("Other", "01/01/2018", "03/01/2020")];
+        let options = Options {
+            show_duration: true,
+            clock: Some(Box::new(FixedClock(chrono::NaiveDate::from_ymd_opt(2022, 6, 1).unwrap()))),
+            ..Options::default()
+        };
+
+        let mut output = Vec::new();
+        write_text(&mut output, &histories, &options).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.contains("End Date: Present\nDuration: 2 years 3 months\n"));
+        assert!(text.contains("End Date: 03/01/2020\nDuration: 2 years 2 months\n"));
+    }
+
+    #[test]
+    fn relative_dates_count_back_from_today() {
+        let histories = vec![
+            entry("Acme", "03/15/2020", "Present"),
+            entry("Recent", "01/01/2021", "01/01/2024"),
+            entry("Other", "01/01/2018", "03/01/2021"),
+            entry("Volunteer", "", ""),
+        ];
+        let options = Options {
+            relative_dates: true,
+            clock: Some(Box::new(FixedClock(NaiveDate::from_ymd_opt(2024, 6, 1).unwrap()))),
+            ..Options::default()
+        };
+
+        let mut output = Vec::new();
+        write_text(&mut output, &histories, &options).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.contains("End Date: Present (ongoing)\n"));
+        assert!(text.contains("End Date: 01/01/2024 (ended 5 months ago)\n"));
+        assert!(text.contains("End Date: 03/01/2021 (ended 3 years ago)\n"));
+
+        let today = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        assert_eq!(relative_end(NaiveDate::from_ymd_opt(2024, 6, 30), today), "ended this month");
+        assert_eq!(relative_end(NaiveDate::from_ymd_opt(2023, 5, 1), today), "ended 1 year ago");
+        assert_eq!(relative_end(NaiveDate::from_ymd_opt(2024, 8, 1), today), "ends in 2 months");
+    }
+
+    #[test]
+    fn multiline_responsibilities_collapse_or_become_bullets() {
+        let data = format!(
+            "{}Acme,Engineer,01/01/2020,Present,\"Springfield, IL\",Jane,\"Built the API\n\n  Led the team\n\",\n",
+            crate::test_support::HEADER
+        );
+        let histories = crate::input::read_work_histories(data.as_bytes(), &Options::default(), &mut Vec::new()).unwrap();
+
+        let mut output = Vec::new();
+        write_text(&mut output, &histories, &Options::default()).unwrap();
+        assert!(String::from_utf8(output).unwrap().contains("Responsibilities: Built the API Led the team\n"));
+
+        let options = Options { multiline_bullets: true, tidy: true, ..Options::default() };
+        let mut output = Vec::new();
+        write_text(&mut output, &histories, &options).unwrap();
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .contains("Responsibilities:\n- Built the API.\n- Led the team.\n\n"));
+    }
+
+    #[test]
+    fn footer_follows_the_last_entry() {
+        let histories = vec![entry("Acme", "03/15/2020", "Present"), entry("Other", "01/01/2018", "03/01/2020")];
+        let options = Options {
+            footer: true,
+            input_path: PathBuf::from("data/input.csv"),
+            clock: Some(Box::new(FixedClock(chrono::NaiveDate::from_ymd_opt(2025, 9, 12).unwrap()))),
+            ..Options::default()
+        };
+
+        let mut output = Vec::new();
+        write_text(&mut output, &histories, &options).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        let expected = format!(
+            "Company: Other\nPosition: Engineer\nStart Date: 01/01/2018\nEnd Date: 03/01/2020\n\
+            Location: Springfield, IL\nResponsibilities: Build things\n\n\
+            Generated by csv_to_work_history_parser v{} on 2025-09-12 from input.csv\n",
+            env!("CARGO_PKG_VERSION")
+        );
+        assert!(text.ends_with(&expected));
+        assert_eq!(text.matches("Generated by").count(), 1);
+    }
+
+    #[test]
+    fn table_aligns_columns_to_the_widest_value() {
+        let histories = vec![entry("Acme Corp", "01/15/2020", "03/01/2022"), entry("Beta", "06/01/2018", "01/01/2020")];
+        let options = Options { format: OutputFormat::Table, ..Options::default() };
+
+        let mut output = Vec::new();
+        write_output(&mut output, &histories, &options).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "+-----------+----------+-------------------+-----------------+\n\
+             | Company   | Position | Dates             | Location        |\n\
+             +-----------+----------+-------------------+-----------------+\n\
+             | Acme Corp | Engineer | 01/2020 – 03/2022 | Springfield, IL |\n\
+             | Beta      | Engineer | 06/2018 – 01/2020 | Springfield, IL |\n\
+             +-----------+----------+-------------------+-----------------+\n"
+        );
+    }
+
+    #[test]
+    fn table_width_truncates_the_widest_columns() {
+        let histories = vec![entry("Acme Corporation International", "01/15/2020", "03/01/2022")];
+        let options = Options { format: OutputFormat::Table, table_width: Some(50), ..Options::default() };
+
+        let mut output = Vec::new();
+        write_output(&mut output, &histories, &options).unwrap();
+        let table = String::from_utf8(output).unwrap();
+
+        assert!(table.lines().all(|line| line.chars().count() <= 50), "{}", table);
+        assert!(table.contains("| Acme Co... | Engineer |"), "{}", table);
+    }
+
+    #[test]
+    fn reference_line_only_written_when_included_and_present() {
+        let mut history = entry("Acme", "01/01/2020", "Present");
+        history.reference_name = Some("Jane Doe".to_string());
+        history.reference_contact = Some("555-123-4567".to_string());
+        let mut nameless = entry("Beta", "01/01/2018", "01/01/2020");
+        nameless.reference_contact = Some("hr@beta.com".to_string());
+        let histories = [history, nameless, entry("Gamma", "01/01/2016", "01/01/2018")];
+
+        let mut output = Vec::new();
+        write_text(&mut output, &histories, &Options::default()).unwrap();
+        assert!(!String::from_utf8(output).unwrap().contains("Reference:"));
+
+        let options = Options { include_reference: true, ..Options::default() };
+        let mut output = Vec::new();
+        write_text(&mut output, &histories, &options).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.contains("Responsibilities: Build things\nReference: Jane Doe, 555-123-4567\n"));
+        assert!(text.contains("Reference: hr@beta.com\n"));
+        assert_eq!(text.matches("Reference:").count(), 2);
+    }
+
+    #[test]
+    fn normalize_phone_formats_us_numbers() {
+        assert_eq!(normalize_phone("5551234567"), "(555) 123-4567");
+        assert_eq!(normalize_phone("555-123-4567"), "(555) 123-4567");
+        assert_eq!(normalize_phone("(555) 123-4567"), "(555) 123-4567");
+        assert_eq!(normalize_phone(" 555.123.4567 "), "(555) 123-4567");
+        assert_eq!(normalize_phone("+1 555 123 4567"), "(555) 123-4567");
+        assert_eq!(normalize_phone("1-555-123-4567"), "(555) 123-4567");
+    }
+
+    #[test]
+    fn normalize_phone_leaves_other_values_alone() {
+        assert_eq!(normalize_phone("+44 20 7946 0958"), "+44 20 7946 0958");
+        assert_eq!(normalize_phone("555-1234"), "555-1234");
+        assert_eq!(normalize_phone("555-123-4567 x12"), "555-123-4567 x12");
+        assert_eq!(normalize_phone("jane@acme.com"), "jane@acme.com");
+    }
+
+    #[test]
+    fn normalize_phone_applies_only_to_included_references() {
+        let mut history = entry("Acme", "01/01/2020", "Present");
+        history.reference_contact = Some("555-123-4567".to_string());
+        let histories = [history];
+
+        let options = Options { include_reference: true, normalize_phone: true, ..Options::default() };
+        let mut output = Vec::new();
+        write_text(&mut output, &histories, &options).unwrap();
+        assert!(String::from_utf8(output).unwrap().contains("Reference: (555) 123-4567\n"));
+
+        let options = Options { normalize_phone: true, ..Options::default() };
+        let mut output = Vec::new();
+        write_text(&mut output, &histories, &options).unwrap();
+        assert!(!String::from_utf8(output).unwrap().contains("555"));
+    }
+
+    #[test]
+    fn timeline_draws_overlapping_jobs_on_separate_rows() {
+        let histories = vec![
+            entry("Beta", "01/01/2021", "01/01/2023"),
+            entry("Acme", "01/01/2020", "01/01/2022"),
+            entry("Volunteer", "", ""),
+        ];
+        let options = Options { format: OutputFormat::Timeline, timeline_width: Some(12), ..Options::default() };
+
+        let mut output = Vec::new();
+        write_output(&mut output, &histories, &options).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "      2020    2022\n\
+             Beta |    ########|\n\
+             Acme |########    |\n"
+        );
+    }
+
+    #[test]
+    fn timeline_runs_ongoing_jobs_until_today() {
+        let histories = vec![entry("Now Co", "01/01/2023", "Present"), entry("Old Co", "01/01/2020", "01/01/2022")];
+        let options = Options {
+            format: OutputFormat::Timeline,
+            timeline_width: Some(10),
+            clock: Some(Box::new(FixedClock(chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()))),
+            ..Options::default()
+        };
+
+        let mut output = Vec::new();
+        write_output(&mut output, &histories, &options).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "        2020  2023\n\
+             Now Co |      ####|\n\
+             Old Co |####      |\n"
+        );
+    }
+
+    #[test]
+    fn create_output_directory_creates_nested_directories() {
+        let root = env::temp_dir().join(format!("work_history_dirs_{}", std::process::id()));
+        let output_path = root.join("a").join("b").join("history.txt");
+
+        create_output_directory(&output_path).unwrap();
+        assert!(root.join("a").join("b").is_dir());
+        assert!(!output_path.exists());
+
+        // An existing directory is fine
+        create_output_directory(&output_path).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn template_reads_back_as_one_complete_entry() {
+        let mut template = Vec::new();
+        write_template(&mut template).unwrap();
+
+        let mut warnings = Vec::new();
+        let histories = crate::input::read_work_histories(template.as_slice(), &Options::default(), &mut warnings).unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(histories.len(), 1);
+
+        let history = &histories[0];
+        assert_eq!(history.company, "Acme Corp");
+        assert_eq!(history.display_company(), "Acme");
+        assert_eq!(history.location, "Springfield, IL");
+        assert_eq!(history.end_date, None);
+        assert_eq!(history.skills, ["Rust", "SQL"]);
+        assert_eq!(history.employment_type.as_deref(), Some("Full-time"));
+        assert_eq!(history.reference_contact.as_deref(), Some("jane.doe@acme.com"));
+    }
+
+    #[test]
+    fn postal_code_line_only_written_when_included_and_present() {
+        let mut history = entry("Acme", "01/01/2020", "Present");
+        history.postal_code = Some("62701-1234".to_string());
+        let histories = [history, entry("Other", "01/01/2018", "01/01/2020")];
+
+        let mut output = Vec::new();
+        write_text(&mut output, &histories, &Options::default()).unwrap();
+        assert!(!String::from_utf8(output).unwrap().contains("Postal Code:"));
+
+        let options = Options { include_zip: true, ..Options::default() };
+        let mut output = Vec::new();
+        write_text(&mut output, &histories, &options).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.contains("Location: Springfield, IL\nPostal Code: 62701-1234\nResponsibilities:"));
+        assert_eq!(text.matches("Postal Code:").count(), 1);
+    }
+
+    #[test]
+    fn several_formats_get_one_file_each_named_by_extension() {
+        let histories = vec![entry("Acme", "01/01/2020", "Present")];
+        let options = Options {
+            format: OutputFormat::Text,
+            additional_formats: vec![OutputFormat::Json],
+            ..Options::default()
+        };
+
+        let files = render_format_files(Path::new("out/history.txt"), &histories, &options).unwrap();
+        assert_eq!(files[0].path, Path::new("out/history.txt"));
+        assert!(String::from_utf8_lossy(&files[0].contents).starts_with("Work History 1\n"));
+        assert_eq!(files[1].path, Path::new("out/history.json"));
+        assert!(String::from_utf8_lossy(&files[1].contents).starts_with("[{\"company\":\"Acme\""));
+
+        let options = Options { additional_formats: vec![OutputFormat::Prose], ..Options::default() };
+        let err = render_format_files(Path::new("history.txt"), &histories, &options).unwrap_err();
+        assert!(err.to_string().contains("history.txt"));
+    }
+
+    #[test]
+    fn icons_prefix_lines_only_when_requested() {
+        let histories = [entry("Acme", "01/01/2020", "Present")];
+
+        let mut output = Vec::new();
+        write_text(&mut output, &histories, &Options::default()).unwrap();
+        assert!(String::from_utf8(output).unwrap().contains("\nCompany: Acme\nPosition: Engineer\n"));
+
+        let options = Options { icons: true, ..Options::default() };
+        let mut output = Vec::new();
+        write_text(&mut output, &histories, &options).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Work History 1\n\
+             🏢 Company: Acme\n\
+             💼 Position: Engineer\n\
+             📅 Start Date: 01/01/2020\n\
+             📅 End Date: Present\n\
+             📍 Location: Springfield, IL\n\
+             Responsibilities: Build things\n\n"
+        );
+    }
+
+    #[test]
+    fn responsibility_bullets_are_sorted_on_request() {
+        let mut history = entry("Acme", "01/01/2020", "Present");
+        history.responsibilities = "Shipped the mobile app\nbuilt CI\nLed a team of four engineers".to_string();
+        let histories = [history];
+        let bullets = |responsibilities_sort| {
+            let options = Options { multiline_bullets: true, responsibilities_sort, ..Options::default() };
+            let mut output = Vec::new();
+            write_text(&mut output, &histories, &options).unwrap();
+            let text = String::from_utf8(output).unwrap();
+            text.lines().filter(|line| line.starts_with("- ")).map(String::from).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            bullets(ResponsibilitiesSort::None),
+            ["- Shipped the mobile app", "- built CI", "- Led a team of four engineers"]
+        );
+        assert_eq!(
+            bullets(ResponsibilitiesSort::Alpha),
+            ["- built CI", "- Led a team of four engineers", "- Shipped the mobile app"]
+        );
+        assert_eq!(
+            bullets(ResponsibilitiesSort::Length),
+            ["- built CI", "- Shipped the mobile app", "- Led a team of four engineers"]
+        );
+    }
+
+    #[test]
+    fn normalize_punctuation_replaces_each_typographic_mark() {
+        assert_eq!(normalize_punctuation("\u{2018}lead\u{2019}"), "'lead'");
+        assert_eq!(normalize_punctuation("it\u{2019}s"), "it's");
+        assert_eq!(normalize_punctuation("\u{201C}Rockstar\u{201D}"), "\"Rockstar\"");
+        assert_eq!(normalize_punctuation("2019\u{2013}2021"), "2019-2021");
+        assert_eq!(normalize_punctuation("shipped\u{2014}on time"), "shipped--on time");
+        assert_eq!(normalize_punctuation("and more\u{2026}"), "and more...");
+        assert_eq!(normalize_punctuation("Café, naïve"), "Café, naïve");
+    }
+
+    #[test]
+    fn table_of_contents_lists_each_entry_heading() {
+        let histories = vec![
+            entry("Acme Corp", "01/15/2020", "Present"),
+            entry("Beta", "06/01/2018", "01/01/2020"),
+            entry("Volunteer", "", ""),
+        ];
+        let options = Options { toc: true, chronological_labels: true, ..Options::default() };
+
+        let mut output = Vec::new();
+        write_text(&mut output, &histories, &options).unwrap();
+        let text = String::from_utf8(output).unwrap();
+
+        assert!(text.starts_with(
+            "Contents\n\
+             - Job #3 of 3 (most recent): Acme Corp — 01/2020 – Present\n\
+             - Job #2 of 3: Beta — 06/2018 – 01/2020\n\
+             - Job #1 of 3: Volunteer\n\n\
+             Job #3 of 3 (most recent)\n"
+        ));
+        // Every listed heading appears above its entry
+        for line in text.lines().skip(1).take(3) {
+            let heading = line.trim_start_matches("- ").split(':').next().unwrap();
+            assert_eq!(text.matches(&format!("{}\nCompany:", heading)).count(), 1, "{}", heading);
+        }
+    }
+
+    #[test]
+    fn numbered_responsibilities_restart_for_each_entry() {
+        let mut acme = entry("Acme", "01/01/2020", "Present");
+        acme.responsibilities = "Built the API; Led the team\nRan hiring".to_string();
+        let mut beta = entry("Beta", "01/01/2018", "01/01/2020");
+        beta.responsibilities = "Fixed bugs; Wrote docs".to_string();
+        let mut gamma = entry("Gamma", "01/01/2016", "01/01/2018");
+        gamma.responsibilities = "Answered phones".to_string();
+        let options = Options { numbered_responsibilities: true, ..Options::default() };
+
+        let mut output = Vec::new();
+        write_text(&mut output, &[acme, beta, gamma], &options).unwrap();
+        let text = String::from_utf8(output).unwrap();
+
+        assert!(text.contains("Responsibilities:\n1. Built the API\n2. Led the team\n3. Ran hiring\n"));
+        assert!(text.contains("Responsibilities:\n1. Fixed bugs\n2. Wrote docs\n"));
+        // A single clause stays on the label line
+        assert!(text.contains("Responsibilities: Answered phones\n"));
+    }
+
+    #[test]
+    fn aggregate_responsibilities_are_deduplicated_and_sorted() {
+        let mut acme = entry("Acme", "01/01/2020", "Present");
+        acme.responsibilities = "Wrote docs; Led the team\nFixed bugs.".to_string();
+        let mut beta = entry("Beta", "01/01/2018", "01/01/2020");
+        beta.responsibilities = "fixed bugs; Answered phones".to_string();
+        let entries = [acme, beta];
+        let text = |aggregate| {
+            let options = Options { aggregate_responsibilities: Some(aggregate), ..Options::default() };
+            let mut output = Vec::new();
+            write_text(&mut output, &entries, &options).unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        let keep = text(AggregateResponsibilities::Keep);
+        assert!(keep.starts_with(
+            "Key Responsibilities\n- Answered phones\n- Fixed bugs.\n- Led the team\n- Wrote docs\n\nWork History 1\n"
+        ));
+        assert!(keep.contains("Responsibilities: fixed bugs; Answered phones\n"));
+
+        let only = text(AggregateResponsibilities::Only);
+        assert!(only.starts_with("Key Responsibilities\n- Answered phones\n"));
+        assert!(!only.contains("Responsibilities:"));
+    }
+
+    #[test]
+    fn ics_writes_an_all_day_event_per_dated_job() {
+        let mut acme = entry("Acme, Inc.", "01/15/2020", "Present");
+        acme.responsibilities = "Built the API; led the team\nRan hiring".to_string();
+        let mut beta = entry("Beta", "06/01/2018", "01/31/2020");
+        beta.location = String::new();
+        beta.responsibilities = String::new();
+        let histories = vec![acme, beta, entry("Volunteer", "", "")];
+        let options = Options {
+            format: OutputFormat::Ics,
+            clock: Some(Box::new(FixedClock(chrono::NaiveDate::from_ymd_opt(2024, 6, 15).unwrap()))),
+            ..Options::default()
+        };
+
+        let mut output = Vec::new();
+        write_output(&mut output, &histories, &options).unwrap();
+        let text = String::from_utf8(output).unwrap();
+
+        assert!(text.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(text.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+        assert_eq!(text.matches("BEGIN:VEVENT").count(), 2);
+        // The ongoing job runs through today; end dates are exclusive
+        assert!(text.contains(
+            "DTSTART;VALUE=DATE:20200115\r\n\
+             DTEND;VALUE=DATE:20240616\r\n\
+             SUMMARY:Engineer at Acme\\, Inc.\r\n\
+             LOCATION:Springfield\\, IL\r\n\
+             DESCRIPTION:Built the API\\; led the team\\nRan hiring\r\n"
+        ));
+        assert!(text.contains("DTEND;VALUE=DATE:20200201\r\nSUMMARY:Engineer at Beta\r\nEND:VEVENT"));
+        assert!(text.lines().all(|line| line.len() <= 76));
+    }
+
+    #[test]
+    fn ics_lines_are_folded_at_75_bytes() {
+        let line = format!("DESCRIPTION:{}", "é".repeat(40));
+        let folded = fold_ics_line(&line);
+        let parts: Vec<&str> = folded.split("\r\n").collect();
+        assert!(parts.len() > 1);
+        assert!(parts.iter().all(|part| part.len() <= 75));
+        assert!(parts[1..].iter().all(|part| part.starts_with(' ')));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+
+    #[test]
+    fn html_writes_an_escaped_article_per_entry() {
+        let mut acme = entry("Acme & Sons", "01/15/2020", "Present");
+        acme.responsibilities = "Built <fast> APIs".to_string();
+        let options = Options { format: OutputFormat::Html, title: Some("Jane's Jobs".to_string()), ..Options::default() };
+
+        let mut output = Vec::new();
+        write_output(&mut output, &[acme, entry("Volunteer", "", "")], &options).unwrap();
+        let text = String::from_utf8(output).unwrap();
+
+        assert!(text.starts_with("<!DOCTYPE html>\n"));
+        assert!(text.contains("<title>Jane&#39;s Jobs</title>"));
+        assert!(text.contains(
+            "<article>\n<h2>Acme &amp; Sons</h2>\n<p>Engineer</p>\n<p>01/2020 – Present</p>\n\
+             <p>Springfield, IL</p>\n<p>Built &lt;fast&gt; APIs</p>\n</article>\n"
+        ));
+        assert!(text.contains("<h2>Volunteer</h2>\n<p>Engineer</p>\n<p>Springfield, IL</p>"));
+        assert!(text.ends_with("</body>\n</html>\n"));
+    }
+
+    #[test]
+    fn company_colors_are_stable_per_company() {
+        assert_eq!(company_color("Acme"), company_color("Acme"));
+        assert_ne!(company_color("Acme"), company_color("Beta"));
+        assert!(company_color("Acme").starts_with("hsl("));
+
+        let histories = vec![
+            entry("Acme", "01/01/2022", "Present"),
+            entry("Beta", "01/01/2020", "01/01/2022"),
+            entry("Acme", "01/01/2018", "01/01/2020"),
+        ];
+        let options = Options { format: OutputFormat::Html, company_colors: true, ..Options::default() };
+        let mut output = Vec::new();
+        write_output(&mut output, &histories, &options).unwrap();
+        let text = String::from_utf8(output).unwrap();
+
+        let acme = format!("<article style=\"background-color: {}\">\n<h2>Acme</h2>", company_color("Acme"));
+        assert_eq!(text.matches(&acme).count(), 2);
+        assert!(text.contains(&format!("<article style=\"background-color: {}\">\n<h2>Beta</h2>", company_color("Beta"))));
+    }
+
+    #[test]
+    fn flatten_writes_multiline_cells_on_one_line() {
+        let data = format!(
+            "{}Acme,Engineer,01/01/2020,Present,\"Springfield, IL\",Jane,\"Built the API\n\n  Led the team\n\",\n",
+            crate::test_support::HEADER
+        );
+        let histories = crate::input::read_work_histories(data.as_bytes(), &Options::default(), &mut Vec::new()).unwrap();
+        let options = Options { format: OutputFormat::Csv, flatten: true, ..Options::default() };
+
+        let mut output = Vec::new();
+        write_output(&mut output, &histories, &options).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.contains(",Built the API / Led the team,"));
+
+        let read = crate::input::read_work_histories(text.as_bytes(), &Options::default(), &mut Vec::new()).unwrap();
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].company, "Acme");
+        assert_eq!(read[0].location, "Springfield, IL");
+        assert_eq!(read[0].responsibilities, "Built the API / Led the team");
+    }
+
+    #[test]
+    fn keywords_line_is_deduplicated_and_optionally_sorted() {
+        let data = format!(
+            "Company,Job Title,Start Date,End Date,Address,Description,Keywords\n{}\n{}\n",
+            "Acme,Engineer,01/01/2020,Present,\"Springfield, IL\",Build,\"Rust, billing, rust, , APIs\"",
+            "Beta,Engineer,01/01/2018,01/01/2020,\"Springfield, IL\",Build,"
+        );
+        let histories = crate::input::read_work_histories(data.as_bytes(), &Options::default(), &mut Vec::new()).unwrap();
+        assert_eq!(histories[0].keywords, ["Rust", "billing", "APIs"]);
+
+        let write = |options: &Options| {
+            let mut output = Vec::new();
+            write_text(&mut output, &histories, options).unwrap();
+            String::from_utf8(output).unwrap()
+        };
+        assert!(!write(&Options::default()).contains("Keywords:"));
+        let text = write(&Options { include_keywords: true, ..Options::default() });
+        assert!(text.contains("Responsibilities: Build\nKeywords: Rust, billing, APIs\n"));
+        assert_eq!(text.matches("Keywords:").count(), 1);
+        let text = write(&Options { include_keywords: true, sort_keywords: true, ..Options::default() });
+        assert!(text.contains("Keywords: APIs, billing, Rust\n"));
+    }
+
+    #[test]
+    fn website_is_written_and_linked() {
+        let data = format!(
+            "Company,Job Title,Start Date,End Date,Address,Description,Website\n{}\n{}\n{}\n",
+            "Acme,Engineer,01/01/2020,Present,\"Springfield, IL\",Build,acme.example.com",
+            "Beta,Engineer,01/01/2018,01/01/2020,\"Springfield, IL\",Build,http://beta.example.com/?a=1&b=2",
+            "Gamma,Engineer,01/01/2016,01/01/2018,\"Springfield, IL\",Build, "
+        );
+        let histories = crate::input::read_work_histories(data.as_bytes(), &Options::default(), &mut Vec::new()).unwrap();
+        assert_eq!(histories[0].company_url.as_deref(), Some("https://acme.example.com"));
+        assert_eq!(histories[1].company_url.as_deref(), Some("http://beta.example.com/?a=1&b=2"));
+        assert_eq!(histories[2].company_url, None);
+
+        let mut output = Vec::new();
+        write_text(&mut output, &histories, &Options::default()).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.contains("Company: Acme\nWebsite: https://acme.example.com\nPosition: Engineer\n"));
+        assert_eq!(text.matches("Website:").count(), 2);
+
+        let options = Options { format: OutputFormat::Html, ..Options::default() };
+        let mut output = Vec::new();
+        write_output(&mut output, &histories, &options).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.contains("<h2><a href=\"http://beta.example.com/?a=1&amp;b=2\">Beta</a></h2>"));
+        assert!(text.contains("<h2>Gamma</h2>"));
+    }
+
+    #[test]
+    fn numbering_can_start_at_any_base() {
+        let histories = vec![entry("Acme", "01/01/2020", "Present"), entry("Beta", "01/01/2018", "01/01/2020")];
+        let options = Options { start_numbering_at: Some(5), ..Options::default() };
+
+        let mut output = Vec::new();
+        write_text(&mut output, &histories, &options).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.starts_with("Work History 5\nCompany: Acme\n"));
+        assert!(text.contains("Work History 6\nCompany: Beta\n"));
+        assert!(!text.contains("Work History 1\n"));
+
+        let options = Options { split_per_entry: true, ..options };
+        let files = render_split_files(Path::new("."), &histories, &options).unwrap();
+        assert_eq!(files[0].path, Path::new("./work_history_5.txt"));
+        assert!(files[1].contents.starts_with(b"Work History 6\n"));
+    }
+
+    #[test]
+    fn output_bom_starts_the_file_with_ef_bb_bf() {
+        let histories = vec![entry("Acme", "01/01/2020", "Present")];
+        let path = Path::new("out.txt");
+
+        let file = render_text_file(path, &histories, &Options::default()).unwrap();
+        assert!(file.contents.starts_with(b"Work History 1\n"));
+
+        let options = Options { output_bom: true, ..Options::default() };
+        let file = render_text_file(path, &histories, &options).unwrap();
+        assert_eq!(file.contents[..3], [0xEF, 0xBB, 0xBF]);
+        assert!(file.contents[3..].starts_with(b"Work History 1\n"));
+
+        let options = Options { output_bom: true, split_per_entry: true, ..Options::default() };
+        let files = render_split_files(Path::new("."), &histories, &options).unwrap();
+        assert!(files[0].contents.starts_with(&UTF8_BOM));
+    }
+
+    #[test]
+    fn output_template_surrounds_the_rendered_entries_exactly() {
+        let histories = vec![entry("Acme", "01/01/2020", "Present")];
+        let mut entries = Vec::new();
+        write_output(&mut entries, &histories, &Options::default()).unwrap();
+        let entries = String::from_utf8(entries).unwrap();
+
+        let template = "<html><body><pre>\n{{entries}}</pre></body></html>\n".to_string();
+        let options = Options { output_template: Some(template), ..Options::default() };
+        let mut output = Vec::new();
+        write_output(&mut output, &histories, &options).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!("<html><body><pre>\n{}</pre></body></html>\n", entries)
+        );
+    }
+
+    #[test]
+    fn output_template_wraps_each_split_file() {
+        let histories = vec![entry("Acme", "01/01/2020", "Present"), entry("Beta", "01/01/2018", "01/01/2020")];
+        let options = Options {
+            split_per_entry: true,
+            output_template: Some("<pre>\n{{entries}}</pre>\n".to_string()),
+            ..Options::default()
+        };
+
+        let files = render_split_files(Path::new("."), &histories, &options).unwrap();
+        assert_eq!(files.len(), 2);
+        for file in &files {
+            let contents = String::from_utf8(file.contents.clone()).unwrap();
+            assert!(contents.starts_with("<pre>\nWork History "));
+            assert!(contents.ends_with("</pre>\n"));
+        }
+    }
+
+    #[test]
+    fn output_template_needs_an_entries_placeholder() {
+        assert_eq!(fill_document_template("a{{entries}}b{{entries}}", b"X").unwrap(), b"aXb{{entries}}");
+        let err = fill_document_template("\\begin{document}\\end{document}", b"X").unwrap_err();
+        assert_eq!(err.to_string(), "The output template has no {{entries}} placeholder");
+    }
+}
@@ -0,0 +1,87 @@
+//! Sanity checks that flag likely typos in otherwise valid input.
+//! 
+//! These never reject the input; each problem found is reported as a
+//! warning so the entries can be corrected and the program re-run.
+
+use chrono::NaiveDate;
+
+use crate::history::{format_date, month_number, WorkHistory};
+
+/// Longest plausible career, in years, from the earliest start date to the
+/// latest end date.
+pub const MAX_CAREER_YEARS: i32 = 60;
+
+/// Runs every sanity check over the entries.
+/// 
+/// # Arguments
+/// * `work_histories` - The parsed entries
+/// * `today` - The date ongoing jobs are measured to
+/// 
+/// # Returns
+/// * `Vec<String>` - One warning per problem found
+pub fn sanity_check(work_histories: &[WorkHistory], today: NaiveDate) -> Vec<String> {
+    check_career_span(work_histories, today).into_iter().collect()
+}
+
+/// Checks that the whole career, from the earliest start date to the latest
+/// end date (today for an ongoing job), spans at most [`MAX_CAREER_YEARS`].
+/// 
+/// A longer span almost always means a mistyped year, such as 1920 for 2020.
+/// 
+/// # Arguments
+/// * `work_histories` - The parsed entries
+/// * `today` - The date ongoing jobs are measured to
+/// 
+/// # Returns
+/// * `Option<String>` - A warning naming the earliest and latest dates, if the span is too long
+pub fn check_career_span(work_histories: &[WorkHistory], today: NaiveDate) -> Option<String> {
+    let earliest = work_histories.iter().filter_map(|history| history.start_date).min()?;
+    let latest = work_histories
+        .iter()
+        .filter(|history| !history.is_undated())
+        .map(|history| history.end_date.unwrap_or(today))
+        .max()?;
+
+    let years = (month_number(latest) - month_number(earliest)) / 12;
+    (years > MAX_CAREER_YEARS).then(|| {
+        format!(
+            "Career spans {} years, from {} to {}; check for a mistyped year",
+            years,
+            format_date(earliest),
+            format_date(latest)
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::entry;
+
+    #[test]
+    fn career_span_over_sixty_years_is_reported() {
+        let today = NaiveDate::from_ymd_opt(2024, 6, 15).unwrap();
+        let histories = vec![
+            entry("Acme", "01/01/2020", "Present"),
+            entry("Typo Co", "01/01/1920", "01/01/1921"),
+        ];
+
+        assert_eq!(
+            check_career_span(&histories, today).unwrap(),
+            "Career spans 104 years, from 01/01/1920 to 06/01/2024; check for a mistyped year"
+        );
+    }
+
+    #[test]
+    fn career_span_within_sixty_years_is_accepted() {
+        let today = NaiveDate::from_ymd_opt(2024, 6, 15).unwrap();
+        let histories = vec![
+            entry("Acme", "01/01/2020", "01/01/2024"),
+            entry("First Job", "01/01/1964", "01/01/1970"),
+            entry("Volunteer", "", ""),
+        ];
+
+        assert!(check_career_span(&histories, today).is_none());
+        assert!(sanity_check(&[], today).is_empty());
+    }
+}
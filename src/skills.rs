@@ -0,0 +1,163 @@
+//! Experience totals per skill, from the optional Skills column.
+//! 
+//! Each job's tenure counts towards every skill tagged on it. Jobs that
+//! overlap in time are only counted once per skill, so holding two jobs with
+//! the same skill for a year adds one year, not two.
+
+use chrono::NaiveDate;
+
+use crate::history::WorkHistory;
+
+/// Total experience with one skill.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkillExperience {
+    /// The skill as first written in the input
+    pub skill: String,
+    /// Months the skill was used, with overlapping jobs counted once
+    pub months: i32,
+}
+
+/// Sums the tenure per skill across all entries.
+/// 
+/// Skills are matched case-insensitively. Ongoing jobs count until `today`;
+/// undated entries don't count.
+/// 
+/// # Arguments
+/// * `work_histories` - The entries to total
+/// * `today` - The date ongoing jobs are measured to
+/// 
+/// # Returns
+/// * `Vec<SkillExperience>` - Totals ordered by experience (most first), then name
+pub fn skill_experience(work_histories: &[WorkHistory], today: NaiveDate) -> Vec<SkillExperience> {
+    // Collect each skill's employment spans, keeping the first spelling seen
+    let mut spans: Vec<(String, Vec<(i32, i32)>)> = Vec::new();
+    for history in work_histories {
+        let Some(span) = history.month_span(today) else {
+            continue;
+        };
+        for skill in &history.skills {
+            match spans.iter_mut().find(|(name, _)| name.eq_ignore_ascii_case(skill)) {
+                Some((_, skill_spans)) => skill_spans.push(span),
+                None => spans.push((skill.clone(), vec![span])),
+            }
+        }
+    }
+
+    let mut totals: Vec<SkillExperience> = spans
+        .into_iter()
+        .map(|(skill, skill_spans)| SkillExperience { skill, months: merged_months(skill_spans) })
+        .collect();
+    totals.sort_by(|a, b| b.months.cmp(&a.months).then_with(|| a.skill.cmp(&b.skill)));
+    totals
+}
+
+/// Counts the months covered by a set of spans, counting overlaps once.
+/// 
+/// # Arguments
+/// * `spans` - Half-open month ranges
+/// 
+/// # Returns
+/// * `i32` - The number of distinct months covered
+fn merged_months(mut spans: Vec<(i32, i32)>) -> i32 {
+    spans.sort();
+
+    let mut total = 0;
+    let mut covered_until = i32::MIN;
+    for (start, end) in spans {
+        let start = start.max(covered_until);
+        if end > start {
+            total += end - start;
+            covered_until = end;
+        }
+    }
+    total
+}
+
+/// Formats a number of months as years and months, e.g. "4 years 2 months".
+/// 
+/// # Arguments
+/// * `months` - The number of months
+/// 
+/// # Returns
+/// * `String` - The human-readable duration
+pub fn format_months(months: i32) -> String {
+    let plural = |count: i32, unit: &str| {
+        if count == 1 {
+            format!("1 {}", unit)
+        } else {
+            format!("{} {}s", count, unit)
+        }
+    };
+
+    match (months / 12, months % 12) {
+        (0, months) => plural(months, "month"),
+        (years, 0) => plural(years, "year"),
+        (years, months) => format!("{} {}", plural(years, "year"), plural(months, "month")),
+    }
+}
+
+/// Formats skill totals as a single report line, e.g. "Python: 6 years, Rust: 4 years".
+/// 
+/// # Arguments
+/// * `totals` - The skill totals to report
+/// 
+/// # Returns
+/// * `String` - The report line
+pub fn format_skill_experience(totals: &[SkillExperience]) -> String {
+    totals
+        .iter()
+        .map(|total| format!("{}: {}", total.skill, format_months(total.months)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::parse_date;
+    use crate::test_support::entry;
+
+    fn tagged(start: &str, end: &str, skills: &[&str]) -> WorkHistory {
+        let mut history = entry("Acme", start, end);
+        history.skills = skills.iter().map(|skill| skill.to_string()).collect();
+        history
+    }
+
+    #[test]
+    fn sums_tenure_per_skill() {
+        let histories = vec![
+            tagged("01/01/2016", "01/01/2020", &["Python", "Rust"]),
+            tagged("01/01/2020", "01/01/2022", &["python"]),
+        ];
+
+        let totals = skill_experience(&histories, parse_date("01/01/2024").unwrap());
+        assert_eq!(format_skill_experience(&totals), "Python: 6 years, Rust: 4 years");
+    }
+
+    #[test]
+    fn overlapping_jobs_count_once() {
+        let histories = vec![
+            tagged("01/01/2018", "01/01/2020", &["Rust"]),
+            tagged("07/01/2019", "07/01/2020", &["Rust"]),
+        ];
+
+        let totals = skill_experience(&histories, parse_date("01/01/2024").unwrap());
+        assert_eq!(totals, [SkillExperience { skill: "Rust".to_string(), months: 30 }]);
+    }
+
+    #[test]
+    fn ongoing_jobs_count_until_today() {
+        let histories = vec![tagged("03/01/2023", "Present", &["Go"])];
+
+        let totals = skill_experience(&histories, parse_date("04/15/2024").unwrap());
+        assert_eq!(format_skill_experience(&totals), "Go: 1 year 1 month");
+    }
+
+    #[test]
+    fn format_months_handles_short_spans() {
+        assert_eq!(format_months(0), "0 months");
+        assert_eq!(format_months(5), "5 months");
+        assert_eq!(format_months(12), "1 year");
+        assert_eq!(format_months(26), "2 years 2 months");
+    }
+}
@@ -0,0 +1,148 @@
+//! Summary statistics over the parsed entries, for reviewing data quality.
+
+use serde::Serialize;
+
+use crate::history::WorkHistory;
+
+/// Responsibilities shorter than this many characters are flagged as
+/// probably missing or unfinished.
+pub const SHORT_RESPONSIBILITIES: usize = 10;
+
+/// Statistics about a set of entries.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Stats {
+    /// Number of entries
+    pub entries: usize,
+    /// Number of ongoing jobs
+    pub current: usize,
+    /// Length statistics of the responsibilities
+    pub responsibilities: LengthStats,
+}
+
+/// Length statistics of one text field, in characters.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LengthStats {
+    /// Length of the longest value
+    pub longest: usize,
+    /// Mean length over all entries
+    pub average: f64,
+    /// Entries whose value is empty or shorter than [`SHORT_RESPONSIBILITIES`]
+    pub short: Vec<ShortEntry>,
+}
+
+/// An entry with a suspiciously short value.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ShortEntry {
+    /// The company, to find the entry by
+    pub company: String,
+    /// Length of the value
+    pub length: usize,
+}
+
+/// Computes the statistics for a set of entries.
+/// 
+/// # Arguments
+/// * `work_histories` - The entries to summarize
+/// 
+/// # Returns
+/// * `Stats` - The statistics
+pub fn compute_stats(work_histories: &[WorkHistory]) -> Stats {
+    let lengths: Vec<usize> = work_histories
+        .iter()
+        .map(|history| history.responsibilities.trim().chars().count())
+        .collect();
+    let short = work_histories
+        .iter()
+        .zip(&lengths)
+        .filter(|(_, &length)| length < SHORT_RESPONSIBILITIES)
+        .map(|(history, &length)| ShortEntry { company: history.display_company().to_string(), length })
+        .collect();
+
+    Stats {
+        entries: work_histories.len(),
+        current: work_histories
+            .iter()
+            .filter(|history| history.end_date.is_none() && !history.is_undated())
+            .count(),
+        responsibilities: LengthStats {
+            longest: lengths.iter().copied().max().unwrap_or(0),
+            average: if lengths.is_empty() {
+                0.0
+            } else {
+                lengths.iter().sum::<usize>() as f64 / lengths.len() as f64
+            },
+            short,
+        },
+    }
+}
+
+/// Formats the statistics as readable text, one statistic per line.
+/// 
+/// # Arguments
+/// * `stats` - The statistics to format
+/// 
+/// # Returns
+/// * `String` - The formatted statistics
+pub fn format_stats(stats: &Stats) -> String {
+    let responsibilities = &stats.responsibilities;
+    let mut text = format!(
+        "Entries: {}\n\
+        Current jobs: {}\n\
+        Longest responsibilities: {} characters\n\
+        Average responsibilities: {:.1} characters\n",
+        stats.entries, stats.current, responsibilities.longest, responsibilities.average
+    );
+
+    if !responsibilities.short.is_empty() {
+        let short: Vec<String> = responsibilities
+            .short
+            .iter()
+            .map(|entry| format!("{} ({})", entry.company, entry.length))
+            .collect();
+        text.push_str(&format!(
+            "Short responsibilities (under {} characters): {}\n",
+            SHORT_RESPONSIBILITIES,
+            short.join(", ")
+        ));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::entry;
+
+    #[test]
+    fn responsibilities_lengths_are_summarized() {
+        let mut described = entry("Acme", "01/01/2020", "Present");
+        described.responsibilities = "Built the billing system".to_string();
+        let mut blank = entry("Side Gig", "01/01/2019", "01/01/2020");
+        blank.responsibilities = "  ".to_string();
+        let mut brief = entry("Temp Co", "01/01/2018", "01/01/2019");
+        brief.responsibilities = "Filing".to_string();
+
+        let stats = compute_stats(&[described, blank, brief]);
+        assert_eq!(stats.entries, 3);
+        assert_eq!(stats.current, 1);
+        assert_eq!(stats.responsibilities.longest, 24);
+        assert_eq!(stats.responsibilities.average, 10.0);
+        assert_eq!(
+            format_stats(&stats),
+            "Entries: 3\n\
+            Current jobs: 1\n\
+            Longest responsibilities: 24 characters\n\
+            Average responsibilities: 10.0 characters\n\
+            Short responsibilities (under 10 characters): Side Gig (0), Temp Co (6)\n"
+        );
+    }
+
+    #[test]
+    fn stats_serialize_to_json() {
+        let stats = compute_stats(&[entry("Acme", "01/01/2020", "Present")]);
+        let json = serde_json::to_value(&stats).unwrap();
+        assert_eq!(json["responsibilities"]["longest"], 12);
+        assert_eq!(json["responsibilities"]["short"], serde_json::json!([]));
+        assert_eq!(compute_stats(&[]).responsibilities.average, 0.0);
+    }
+}